@@ -1,4 +1,37 @@
-use bevy::prelude::*;
+//! Music that changes with altitude, and sound effects for what happens in a run.
+//!
+//! Sound effects are played from gameplay events, so the systems that send them don't need to
+//! know about audio. Hazard sounds are panned by where the hazard is relative to the camera,
+//! so an enemy can be heard coming before it's on screen.
+
+use bevy::{
+    audio::{PlaybackMode, SpatialScale, Volume},
+    prelude::*,
+};
+use rand::prelude::*;
+
+use crate::{
+    game::{Score, Surface},
+    settings::{AudioBus, AudioSettings},
+    EnemyAlerted, GameCamera, NearMiss, PlatformBounced, PlayerDied,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SoundEffects>()
+        .init_resource::<MusicConfig>()
+        .add_systems(
+            Update,
+            (
+                play_bounce_sound.run_if(on_event::<PlatformBounced>()),
+                play_death_sound.run_if(on_event::<PlayerDied>()),
+                add_spatial_listener,
+                play_enemy_alert_sound.run_if(on_event::<EnemyAlerted>()),
+                play_near_miss_sound.run_if(on_event::<NearMiss>()),
+                (switch_music_band, fade_music).chain(),
+                apply_sound_effect_volume.run_if(resource_changed::<AudioSettings>),
+            ),
+        );
+}
 
 /// An organizational marker component that should be added to a spawned [`AudioBundle`] if it is in the
 /// general "music" category (ex: global background music, soundtrack, etc).
@@ -35,3 +68,279 @@ pub struct Music;
 /// ```
 #[derive(Component, Default)]
 pub struct SoundEffect;
+
+#[derive(Resource, Debug)]
+struct SoundEffects {
+    /// One is picked at random for each bounce, so they don't get repetitive.
+    bounces: Vec<Handle<AudioSource>>,
+    spring: Handle<AudioSource>,
+    death: Handle<AudioSource>,
+    enemy_alert: Handle<AudioSource>,
+    near_miss: Handle<AudioSource>,
+}
+impl FromWorld for SoundEffects {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self {
+            bounces: (1..=4)
+                .map(|step| asset_server.load(format!("audio/sound_effects/step{step}.ogg")))
+                .collect(),
+            spring: asset_server.load("audio/sound_effects/spring.ogg"),
+            death: asset_server.load("audio/sound_effects/step1.ogg"),
+            enemy_alert: asset_server.load("audio/sound_effects/button_press.ogg"),
+            near_miss: asset_server.load("audio/sound_effects/button_hover.ogg"),
+        }
+    }
+}
+impl SoundEffects {
+    /// How much slower the death sound plays than a bounce, for a heavier thud.
+    const DEATH_SPEED: f32 = 0.5;
+}
+
+fn play_sound_effect(
+    commands: &mut Commands,
+    audio_settings: &AudioSettings,
+    source: Handle<AudioSource>,
+    speed: f32,
+) {
+    commands.spawn((
+        Name::new("Sound effect"),
+        AudioBundle {
+            source,
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new(audio_settings.output(AudioBus::SoundEffects)),
+                speed,
+                ..default()
+            },
+        },
+        SoundEffect,
+    ));
+}
+
+fn play_bounce_sound(
+    mut commands: Commands,
+    mut bounced: EventReader<PlatformBounced>,
+    sound_effects: Res<SoundEffects>,
+    audio_settings: Res<AudioSettings>,
+) {
+    // several bounces between two frames would just sound like one louder one, and a spring
+    // drowns out the rest
+    let springs = bounced
+        .read()
+        .filter(|bounce| bounce.surface == Surface::Spring)
+        .count();
+    if springs > 0 {
+        play_sound_effect(
+            &mut commands,
+            &audio_settings,
+            sound_effects.spring.clone(),
+            1.0,
+        );
+        return;
+    }
+    if let Some(source) = sound_effects.bounces.choose(&mut thread_rng()) {
+        play_sound_effect(&mut commands, &audio_settings, source.clone(), 1.0);
+    }
+}
+
+/// Touching any hazard kills, so this is also the sound of taking damage.
+fn play_death_sound(
+    mut commands: Commands,
+    mut died: EventReader<PlayerDied>,
+    sound_effects: Res<SoundEffects>,
+    audio_settings: Res<AudioSettings>,
+) {
+    died.clear();
+    play_sound_effect(
+        &mut commands,
+        &audio_settings,
+        sound_effects.death.clone(),
+        SoundEffects::DEATH_SPEED,
+    );
+}
+
+/// World units per unit of distance for hazard sounds. Sounds get quieter with the square of
+/// the distance, so this sets how far away a hazard can still be heard.
+const HAZARD_SOUND_SCALE: f32 = 1.0 / 200.0;
+
+/// Hazard sounds are heard from the camera.
+fn add_spatial_listener(
+    mut commands: Commands,
+    camera_query: Query<Entity, (With<GameCamera>, Without<SpatialListener>)>,
+) {
+    for camera in camera_query.iter() {
+        commands.entity(camera).insert(SpatialListener::default());
+    }
+}
+
+fn play_hazard_sound(
+    commands: &mut Commands,
+    audio_settings: &AudioSettings,
+    source: Handle<AudioSource>,
+    position: Vec2,
+) {
+    commands.spawn((
+        Name::new("Hazard sound"),
+        AudioBundle {
+            source,
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new(audio_settings.output(AudioBus::SoundEffects)),
+                spatial: true,
+                spatial_scale: Some(SpatialScale::new_2d(HAZARD_SOUND_SCALE)),
+                ..default()
+            },
+        },
+        SpatialBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+        SoundEffect,
+    ));
+}
+
+fn play_enemy_alert_sound(
+    mut commands: Commands,
+    mut alerted: EventReader<EnemyAlerted>,
+    sound_effects: Res<SoundEffects>,
+    audio_settings: Res<AudioSettings>,
+) {
+    for enemy in alerted.read() {
+        play_hazard_sound(
+            &mut commands,
+            &audio_settings,
+            sound_effects.enemy_alert.clone(),
+            enemy.position,
+        );
+    }
+}
+
+fn play_near_miss_sound(
+    mut commands: Commands,
+    mut near_missed: EventReader<NearMiss>,
+    sound_effects: Res<SoundEffects>,
+    audio_settings: Res<AudioSettings>,
+) {
+    for near_miss in near_missed.read() {
+        play_hazard_sound(
+            &mut commands,
+            &audio_settings,
+            sound_effects.near_miss.clone(),
+            near_miss.position,
+        );
+    }
+}
+
+/// Which track plays at which height. Moving into another band crossfades to its track.
+#[derive(Resource, Debug, Clone)]
+pub struct MusicConfig {
+    /// Lowest first. The first band plays in menus too, and from the start of every run.
+    pub bands: Vec<MusicBand>,
+    /// How long a crossfade takes, in seconds.
+    pub fade_seconds: f32,
+}
+impl Default for MusicConfig {
+    fn default() -> Self {
+        Self {
+            bands: vec![
+                MusicBand {
+                    from_height: 0.0,
+                    track: "audio/music/Fluffing A Duck.ogg".to_string(),
+                },
+                MusicBand {
+                    from_height: 5_000.0,
+                    track: "audio/music/Monkeys Spinning Monkeys.ogg".to_string(),
+                },
+            ],
+            fade_seconds: 3.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MusicBand {
+    /// The best height of the run where the band starts.
+    pub from_height: f32,
+    pub track: String,
+}
+
+/// A playing music track, fading in unless it is on its way out.
+#[derive(Component, Debug)]
+struct MusicTrack {
+    band: usize,
+    fading_out: bool,
+    /// How far it has faded in, from 0 to 1. Scaled by the music volume when played.
+    fade: f32,
+}
+
+/// Starts the track for the band the run has reached, and fades out the one before it.
+/// Going by the best height means falling back down doesn't switch back.
+fn switch_music_band(
+    mut commands: Commands,
+    config: Res<MusicConfig>,
+    asset_server: Res<AssetServer>,
+    score: Option<Res<Score>>,
+    mut track_query: Query<&mut MusicTrack>,
+) {
+    if config.bands.is_empty() {
+        return;
+    }
+    let height = score.map_or(0.0, |score| score.height);
+    let band = config
+        .bands
+        .iter()
+        .rposition(|band| height >= band.from_height)
+        .unwrap_or(0);
+    if track_query
+        .iter()
+        .any(|track| track.band == band && !track.fading_out)
+    {
+        return;
+    }
+    for mut track in track_query.iter_mut() {
+        track.fading_out = true;
+    }
+    commands.spawn((
+        Name::new("Music"),
+        AudioBundle {
+            source: asset_server.load(&config.bands[band].track),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::ZERO),
+        },
+        Music,
+        MusicTrack {
+            band,
+            fading_out: false,
+            fade: 0.0,
+        },
+    ));
+}
+
+fn fade_music(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<MusicConfig>,
+    audio_settings: Res<AudioSettings>,
+    mut track_query: Query<(Entity, &mut MusicTrack, &AudioSink)>,
+) {
+    let step = time.delta_seconds() / config.fade_seconds.max(f32::EPSILON);
+    for (entity, mut track, sink) in track_query.iter_mut() {
+        if track.fading_out {
+            track.fade -= step;
+            if track.fade <= 0.0 {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        } else {
+            track.fade = (track.fade + step).min(1.0);
+        }
+        sink.set_volume(track.fade * audio_settings.output(AudioBus::Music));
+    }
+}
+
+/// Sound effects are short, but changing the volume shouldn't wait for the next one.
+fn apply_sound_effect_volume(
+    audio_settings: Res<AudioSettings>,
+    sink_query: Query<&AudioSink, With<SoundEffect>>,
+) {
+    for sink in sink_query.iter() {
+        sink.set_volume(audio_settings.output(AudioBus::SoundEffects));
+    }
+}
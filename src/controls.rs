@@ -0,0 +1,102 @@
+//! The controls screen, where players rebind actions to their own keys and buttons.
+//!
+//! The screen itself is navigated with fixed keys and buttons, so a bad binding can't lock
+//! anyone out of fixing it.
+
+use bevy::prelude::*;
+
+use crate::{
+    input::{Action, ActionBindings, Binding},
+    persistence::Saves,
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Controls), reset_controls_screen)
+        .add_systems(
+            Update,
+            handle_controls_input.run_if(in_state(Screen::Controls)),
+        )
+        .add_systems(OnExit(Screen::Controls), save_bindings);
+}
+
+/// What the controls screen is doing.
+#[derive(Resource, Debug, Default)]
+pub struct ControlsScreen {
+    /// Index into [`Action::ALL`].
+    pub selected: usize,
+    /// Waiting for a key or button to bind to the selected action.
+    pub capturing: bool,
+    /// The outcome of the last change, like which action lost a binding to it.
+    pub message: Option<String>,
+}
+impl ControlsScreen {
+    pub fn selected_action(&self) -> Action {
+        Action::ALL[self.selected]
+    }
+}
+
+fn reset_controls_screen(mut commands: Commands) {
+    commands.insert_resource(ControlsScreen::default());
+}
+
+fn handle_controls_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut controls: ResMut<ControlsScreen>,
+    mut bindings: ResMut<ActionBindings>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let action = controls.selected_action();
+    if controls.capturing {
+        if keys.just_pressed(KeyCode::Escape) {
+            controls.capturing = false;
+            controls.message = None;
+            return;
+        }
+        let pressed = keys.get_just_pressed().next().map(|&key| Binding::Key(key));
+        let pressed = pressed.or_else(|| {
+            gamepad_buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Gamepad(button.button_type))
+        });
+        let Some(binding) = pressed else {
+            return;
+        };
+        let taken_from = bindings.bind(action, binding);
+        controls.capturing = false;
+        controls.message = (!taken_from.is_empty()).then(|| {
+            let names: Vec<_> = taken_from.iter().map(ToString::to_string).collect();
+            format!("{binding} was unbound from {}", names.join(", "))
+        });
+        return;
+    }
+
+    let gamepad_pressed = |button_type| {
+        gamepad_buttons
+            .get_just_pressed()
+            .any(|button| button.button_type == button_type)
+    };
+    if keys.just_pressed(KeyCode::ArrowUp) || gamepad_pressed(GamepadButtonType::DPadUp) {
+        controls.selected = controls
+            .selected
+            .checked_sub(1)
+            .unwrap_or(Action::ALL.len() - 1);
+    } else if keys.just_pressed(KeyCode::ArrowDown) || gamepad_pressed(GamepadButtonType::DPadDown)
+    {
+        controls.selected = (controls.selected + 1) % Action::ALL.len();
+    } else if keys.just_pressed(KeyCode::Enter) || gamepad_pressed(GamepadButtonType::South) {
+        controls.capturing = true;
+        controls.message = None;
+    } else if keys.just_pressed(KeyCode::Backspace) || gamepad_pressed(GamepadButtonType::West) {
+        bindings.clear(action);
+        controls.message = Some(format!("{action} is unbound"));
+    } else if keys.just_pressed(KeyCode::Escape) || gamepad_pressed(GamepadButtonType::East) {
+        next_screen.set(Screen::MainMenu);
+    }
+}
+
+fn save_bindings(saves: Res<Saves>, bindings: Res<ActionBindings>) {
+    saves.save(ActionBindings::FILE_NAME, &*bindings);
+}
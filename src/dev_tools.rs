@@ -1,25 +0,0 @@
-//! Development tools for the game. This plugin is only enabled in dev builds.
-
-use bevy::{
-    dev_tools::ui_debug_overlay::{DebugUiPlugin, UiDebugOptions},
-    input::common_conditions::input_just_pressed,
-    prelude::*,
-};
-
-pub(super) fn plugin(app: &mut App) {
-    // Log `Screen` state transitions.
-    //    app.add_systems(Update, log_transitions::<Screen>);
-
-    // Toggle the debug overlay for UI.
-    app.add_plugins(DebugUiPlugin);
-    app.add_systems(
-        Update,
-        toggle_debug_ui.run_if(input_just_pressed(TOGGLE_KEY)),
-    );
-}
-
-const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
-
-fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
-    options.toggle();
-}
@@ -0,0 +1,137 @@
+//! An overlay estimating input-to-photon latency, for tuning the fixed step and render
+//! interpolation.
+//!
+//! A press is timestamped by the first frame that sees it, and counts as handled at the end of
+//! the first frame that ran a fixed step after it, since that's when it can first move the
+//! player. One more frame is added for pipelined rendering to present it. The OS, compositor
+//! and display add latency the game can't see, so the real figure is higher.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    input::{common_conditions::input_just_pressed, InputSystem},
+    prelude::*,
+    utils::{Duration, Instant},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LatencyProbe>()
+        .add_systems(PreUpdate, timestamp_presses.after(InputSystem))
+        .add_systems(FixedFirst, mark_fixed_step)
+        .add_systems(Last, finish_measurement)
+        .add_systems(
+            Update,
+            (
+                toggle_latency_overlay.run_if(input_just_pressed(TOGGLE_KEY)),
+                update_latency_overlay,
+            )
+                .chain(),
+        );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+/// How many of the latest presses the figures are taken over.
+const SAMPLES: usize = 30;
+
+#[derive(Resource, Default)]
+struct LatencyProbe {
+    /// When the press being measured was first seen.
+    pressed_at: Option<Instant>,
+    /// Whether a fixed step has run since.
+    stepped: bool,
+    samples: VecDeque<Duration>,
+}
+
+#[derive(Component)]
+struct LatencyOverlay;
+
+fn timestamp_presses(
+    mut probe: ResMut<LatencyProbe>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    touches: Res<Touches>,
+) {
+    // presses during a measurement are covered by it
+    if probe.pressed_at.is_some() {
+        return;
+    }
+    if keys.get_just_pressed().next().is_some()
+        || gamepad_buttons.get_just_pressed().next().is_some()
+        || touches.any_just_pressed()
+    {
+        probe.pressed_at = Some(Instant::now());
+        probe.stepped = false;
+    }
+}
+
+fn mark_fixed_step(mut probe: ResMut<LatencyProbe>) {
+    if probe.pressed_at.is_some() {
+        probe.stepped = true;
+    }
+}
+
+fn finish_measurement(time: Res<Time<Real>>, mut probe: ResMut<LatencyProbe>) {
+    let Some(pressed_at) = probe.pressed_at else {
+        return;
+    };
+    if !probe.stepped {
+        return;
+    }
+    // the render world presents this frame while the next one is simulated
+    let latency = pressed_at.elapsed() + time.delta();
+    if probe.samples.len() == SAMPLES {
+        probe.samples.pop_front();
+    }
+    probe.samples.push_back(latency);
+    probe.pressed_at = None;
+}
+
+fn toggle_latency_overlay(
+    mut commands: Commands,
+    overlay_query: Query<Entity, With<LatencyOverlay>>,
+) {
+    if let Ok(overlay) = overlay_query.get_single() {
+        commands.entity(overlay).despawn_recursive();
+        return;
+    }
+    commands.spawn((
+        Name::new("Latency overlay"),
+        LatencyOverlay,
+        TextBundle::from_section("", TextStyle::default()).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+    ));
+}
+
+fn update_latency_overlay(
+    probe: Res<LatencyProbe>,
+    real_time: Res<Time<Real>>,
+    fixed_time: Res<Time<Fixed>>,
+    mut overlay_query: Query<&mut Text, With<LatencyOverlay>>,
+) {
+    let Ok(mut text) = overlay_query.get_single_mut() else {
+        return;
+    };
+    let millis = |duration: Duration| duration.as_secs_f32() * 1000.0;
+    let latency = match (probe.samples.iter().min(), probe.samples.iter().max()) {
+        (Some(&min), Some(&max)) => {
+            let mean = probe.samples.iter().sum::<Duration>() / probe.samples.len() as u32;
+            format!(
+                "Input latency: ~{:.1} ms (min {:.1}, max {:.1}, last {} presses)",
+                millis(mean),
+                millis(min),
+                millis(max),
+                probe.samples.len()
+            )
+        }
+        _ => "Input latency: press something".to_string(),
+    };
+    text.sections[0].value = format!(
+        "{latency}\nFrame: {:.1} ms\nFixed step: {:.1} ms",
+        millis(real_time.delta()),
+        millis(fixed_time.timestep())
+    );
+}
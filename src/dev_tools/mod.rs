@@ -0,0 +1,46 @@
+//! Development tools for the game. This plugin is only enabled in dev builds.
+
+mod latency;
+mod overview;
+mod schedule_graph;
+
+use bevy::{
+    dev_tools::{
+        states::log_transitions,
+        ui_debug_overlay::{DebugUiPlugin, UiDebugOptions},
+    },
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+use crate::screen::{Pause, Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    // Log `Screen` and `Pause` state transitions.
+    app.add_systems(
+        Update,
+        (log_transitions::<Screen>, log_transitions::<Pause>),
+    );
+
+    // Open a second window showing the whole generated world.
+    app.add_plugins(overview::plugin);
+
+    // Estimate input latency, shown in an overlay.
+    app.add_plugins(latency::plugin);
+
+    // Export the schedules' system ordering as graphs.
+    app.add_plugins(schedule_graph::plugin);
+
+    // Toggle the debug overlay for UI.
+    app.add_plugins(DebugUiPlugin);
+    app.add_systems(
+        Update,
+        toggle_debug_ui.run_if(input_just_pressed(TOGGLE_KEY)),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
+
+fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
+    options.toggle();
+}
@@ -0,0 +1,112 @@
+//! A second window with a zoomed-out view of everything that has been generated.
+
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    render::{camera::RenderTarget, view::RenderLayers},
+    window::WindowRef,
+};
+
+use crate::game::{Player, ScreenHeight};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_gizmo_group::<OverviewGizmos>()
+        .add_systems(Startup, configure_overview_gizmos)
+        .add_systems(
+            Update,
+            (
+                toggle_overview_window.run_if(input_just_pressed(TOGGLE_KEY)),
+                despawn_orphaned_overview_cameras,
+                (follow_screen_height, draw_player_dot),
+            )
+                .chain(),
+        );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F2;
+/// How many times more of the world the overview shows than the main camera.
+const ZOOM_OUT: f32 = 4.0;
+/// Only the overview camera renders this layer, so the player dot doesn't show up in the game.
+const OVERVIEW_LAYER: usize = 1;
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct OverviewGizmos;
+
+#[derive(Component)]
+struct OverviewWindow;
+
+#[derive(Component)]
+struct OverviewCamera {
+    window: Entity,
+}
+
+fn configure_overview_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<OverviewGizmos>();
+    config.render_layers = RenderLayers::layer(OVERVIEW_LAYER);
+}
+
+fn toggle_overview_window(
+    mut commands: Commands,
+    window_query: Query<Entity, With<OverviewWindow>>,
+) {
+    if let Ok(window) = window_query.get_single() {
+        // the camera goes with it, see `despawn_orphaned_overview_cameras`
+        commands.entity(window).despawn();
+        return;
+    }
+    let window = commands
+        .spawn((
+            Name::new("Overview window"),
+            OverviewWindow,
+            Window {
+                title: "Jumper overview".to_string(),
+                resolution: (320.0, 640.0).into(),
+                ..default()
+            },
+        ))
+        .id();
+    let mut camera_bundle = Camera2dBundle::default();
+    camera_bundle.camera.target = RenderTarget::Window(WindowRef::Entity(window));
+    camera_bundle.projection.scale = ZOOM_OUT;
+    commands.spawn((
+        Name::new("Overview camera"),
+        OverviewCamera { window },
+        camera_bundle,
+        RenderLayers::from_layers(&[0, OVERVIEW_LAYER]),
+    ));
+}
+
+/// Closing the window through the OS despawns it without us noticing.
+fn despawn_orphaned_overview_cameras(
+    mut commands: Commands,
+    camera_query: Query<(Entity, &OverviewCamera)>,
+    window_query: Query<(), With<Window>>,
+) {
+    for (camera_entity, overview_camera) in camera_query.iter() {
+        if window_query.get(overview_camera.window).is_err() {
+            commands.entity(camera_entity).despawn();
+        }
+    }
+}
+
+fn follow_screen_height(
+    screen_height: Res<ScreenHeight>,
+    mut camera_query: Query<&mut Transform, With<OverviewCamera>>,
+) {
+    for mut camera_transform in camera_query.iter_mut() {
+        camera_transform.translation.y = screen_height.0;
+    }
+}
+
+fn draw_player_dot(
+    mut gizmos: Gizmos<OverviewGizmos>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    for player_transform in player_query.iter() {
+        gizmos.circle_2d(
+            player_transform.translation.truncate(),
+            8.0 * ZOOM_OUT,
+            Color::srgb(1.0, 0.2, 0.2),
+        );
+    }
+}
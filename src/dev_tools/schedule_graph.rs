@@ -0,0 +1,159 @@
+//! Writes every schedule's systems, sets and ordering constraints out as Mermaid and DOT graphs,
+//! to check how the plugins' systems end up ordered against each other.
+//!
+//! The export runs in `Last`, so `Last` itself (and `Main`, which runs it) are missing: Bevy
+//! takes a schedule out of the world while it runs.
+
+use std::{fmt::Write as _, path::Path};
+
+use bevy::{
+    ecs::schedule::{NodeId, ScheduleGraph},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    utils::get_short_name,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Last,
+        export_schedule_graphs.run_if(input_just_pressed(EXPORT_KEY)),
+    );
+}
+
+const EXPORT_KEY: KeyCode = KeyCode::F4;
+const EXPORT_DIR: &str = "schedule_graphs";
+
+/// A schedule's graph, with each system's own set folded into the system.
+struct GraphExport {
+    /// Id, label, and whether it's a set.
+    nodes: Vec<(String, String, bool)>,
+    /// Set, then what's in it.
+    membership: Vec<(String, String)>,
+    /// What runs first, then what runs after it.
+    ordering: Vec<(String, String)>,
+}
+
+fn export_schedule_graphs(world: &mut World) {
+    let dir = Path::new(EXPORT_DIR);
+    if let Err(error) = std::fs::create_dir_all(dir) {
+        warn!("Could not create {EXPORT_DIR}: {error}");
+        return;
+    }
+    let schedules = world.resource::<Schedules>();
+    for (label, schedule) in schedules.iter() {
+        let name = format!("{label:?}");
+        let file_name: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let export = GraphExport::new(schedule.graph());
+        for (extension, contents) in [("mmd", export.mermaid()), ("dot", export.dot(&name))] {
+            let path = dir.join(format!("{file_name}.{extension}"));
+            if let Err(error) = std::fs::write(&path, contents) {
+                warn!("Could not write {}: {error}", path.display());
+            }
+        }
+    }
+    eprintln!("Exported schedule graphs to {EXPORT_DIR}/");
+}
+
+impl GraphExport {
+    fn new(graph: &ScheduleGraph) -> Self {
+        let hierarchy: Vec<(NodeId, NodeId)> = graph
+            .hierarchy()
+            .graph()
+            .all_edges()
+            .map(|(set, member, _)| (set, member))
+            .collect();
+        let is_system_type_set =
+            |id: NodeId| id.is_set() && graph.set_at(id).system_type().is_some();
+        // ordering against a system function goes through a set only that system is in
+        let resolve = |id: NodeId| -> Vec<NodeId> {
+            if is_system_type_set(id) {
+                hierarchy
+                    .iter()
+                    .filter(|(set, _)| *set == id)
+                    .map(|&(_, member)| member)
+                    .collect()
+            } else {
+                vec![id]
+            }
+        };
+
+        let mut nodes: Vec<_> = graph
+            .systems()
+            .map(|(id, system, _)| (node_id(id), get_short_name(&system.name()), false))
+            .collect();
+        nodes.extend(
+            graph
+                .system_sets()
+                .filter(|&(id, _, _)| !is_system_type_set(id))
+                .map(|(id, set, _)| (node_id(id), format!("{set:?}"), true)),
+        );
+        let membership = hierarchy
+            .iter()
+            .filter(|&&(set, _)| !is_system_type_set(set))
+            .map(|&(set, member)| (node_id(set), node_id(member)))
+            .collect();
+        let ordering = graph
+            .dependency()
+            .graph()
+            .all_edges()
+            .flat_map(|(before, after, _)| {
+                let afters = resolve(after);
+                resolve(before).into_iter().flat_map(move |before| {
+                    afters.clone().into_iter().map(move |after| (before, after))
+                })
+            })
+            .map(|(before, after)| (node_id(before), node_id(after)))
+            .collect();
+        Self {
+            nodes,
+            membership,
+            ordering,
+        }
+    }
+
+    fn mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for (id, label, is_set) in &self.nodes {
+            let label = label.replace('"', "#quot;");
+            let _ = if *is_set {
+                writeln!(out, "    {id}([\"{label}\"])")
+            } else {
+                writeln!(out, "    {id}[\"{label}\"]")
+            };
+        }
+        for (set, member) in &self.membership {
+            let _ = writeln!(out, "    {set} -.- {member}");
+        }
+        for (before, after) in &self.ordering {
+            let _ = writeln!(out, "    {before} --> {after}");
+        }
+        out
+    }
+
+    fn dot(&self, name: &str) -> String {
+        let mut out = format!("digraph \"{}\" {{\n", name.replace('"', "\\\""));
+        for (id, label, is_set) in &self.nodes {
+            let shape = if *is_set { "ellipse" } else { "box" };
+            let label = label.replace('"', "\\\"");
+            let _ = writeln!(out, "    {id} [label=\"{label}\", shape={shape}];");
+        }
+        for (set, member) in &self.membership {
+            let _ = writeln!(out, "    {set} -> {member} [style=dashed, arrowhead=none];");
+        }
+        for (before, after) in &self.ordering {
+            let _ = writeln!(out, "    {before} -> {after};");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn node_id(id: NodeId) -> String {
+    match id {
+        NodeId::System(index) => format!("system{index}"),
+        NodeId::Set(index) => format!("set{index}"),
+    }
+}
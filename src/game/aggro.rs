@@ -0,0 +1,75 @@
+//! Enemies noticing the player: they turn to face them, redden and speed up while alert.
+
+use bevy::prelude::*;
+
+use super::{physics::Interpolator, Player};
+use crate::screen::Pause;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<EnemyAlerted>()
+        .add_systems(FixedUpdate, update_aggro.run_if(in_state(Pause::Running)));
+}
+
+/// Enemies become alert when the player comes this close...
+const ALERT_DISTANCE: f32 = 300.0;
+/// ...and calm down once they are this far away again, so they don't flicker at the edge.
+const CALM_DISTANCE: f32 = 400.0;
+/// How much faster alert enemies move along their path.
+const ALERT_SPEED: f32 = 1.6;
+const ALERT_TINT: Color = Color::srgb(1.0, 0.55, 0.55);
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Aggro {
+    #[default]
+    Idle,
+    Alert,
+}
+
+/// Sent when an enemy notices the player.
+#[derive(Event, Debug)]
+pub struct EnemyAlerted {
+    pub position: Vec2,
+}
+
+fn update_aggro(
+    mut alerted: EventWriter<EnemyAlerted>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<
+        (&Transform, &mut Aggro, &mut Interpolator, &mut Sprite),
+        Without<Player>,
+    >,
+) {
+    let player_position = player_query
+        .get_single()
+        .ok()
+        .map(|player_transform| player_transform.translation.truncate());
+    for (enemy_transform, mut aggro, mut interpolator, mut sprite) in enemy_query.iter_mut() {
+        let enemy_position = enemy_transform.translation.truncate();
+        let distance = player_position.map_or(f32::INFINITY, |player_position| {
+            player_position.distance(enemy_position)
+        });
+        let next = match *aggro {
+            Aggro::Idle if distance <= ALERT_DISTANCE => Aggro::Alert,
+            Aggro::Alert if distance > CALM_DISTANCE => Aggro::Idle,
+            unchanged => unchanged,
+        };
+        if next != *aggro {
+            *aggro = next;
+            let (speed, tint) = match next {
+                Aggro::Idle => (1.0, Color::WHITE),
+                Aggro::Alert => (ALERT_SPEED, ALERT_TINT),
+            };
+            interpolator.speed = speed;
+            sprite.color = tint;
+            if next == Aggro::Alert {
+                alerted.send(EnemyAlerted {
+                    position: enemy_position,
+                });
+            }
+        }
+        if let (Aggro::Alert, Some(player_position)) = (next, player_position) {
+            // the eyes sit left of center in the image, so flipping makes it look right
+            sprite.flip_x = player_position.x > enemy_position.x;
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Optional assists for new players.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use super::{GameTuning, GravityScale, Player, SpeedLimits, Velocity};
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TrajectoryAssist>().add_systems(
+        Update,
+        (
+            toggle_trajectory_assist.run_if(input_just_pressed(TOGGLE_KEY)),
+            draw_predicted_trajectory.run_if(
+                in_state(Screen::InGame).and_then(|assist: Res<TrajectoryAssist>| assist.enabled),
+            ),
+        ),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::KeyT;
+
+/// Draws a faint arc along the path the player will take if they stop steering,
+/// to help judge whether the next platform is in reach.
+#[derive(Resource, Debug, Default)]
+pub struct TrajectoryAssist {
+    pub enabled: bool,
+}
+
+fn toggle_trajectory_assist(mut assist: ResMut<TrajectoryAssist>) {
+    assist.enabled = !assist.enabled;
+}
+
+fn draw_predicted_trajectory(
+    mut gizmos: Gizmos,
+    tuning: Res<GameTuning>,
+    player_query: Query<
+        (
+            &Transform,
+            &Velocity,
+            Option<&GravityScale>,
+            Option<&SpeedLimits>,
+        ),
+        With<Player>,
+    >,
+) {
+    const PREDICTED_SECONDS: f32 = 2.0;
+    const STEP_SECONDS: f32 = 1.0 / 30.0;
+    let Ok((player_transform, player_velocity, gravity_scale, speed_limits)) =
+        player_query.get_single()
+    else {
+        return;
+    };
+    let gravity_scale = gravity_scale.map_or(1.0, |scale| scale.0);
+    let gravity = tuning.gravity * gravity_scale;
+    // same integration as `apply_gravity` and `step_physics`, just with a coarser step
+    let mut position = player_transform.translation.truncate();
+    let mut velocity = player_velocity.0;
+    let steps = (PREDICTED_SECONDS / STEP_SECONDS) as usize;
+    let points = std::iter::once(position).chain((0..steps).map(|_| {
+        velocity.y -= gravity * STEP_SECONDS;
+        if let Some(speed_limits) = speed_limits {
+            velocity = speed_limits.clamp(velocity, gravity_scale);
+        }
+        position += velocity * STEP_SECONDS;
+        position
+    }));
+    gizmos.linestrip_2d(points, Color::srgba(1.0, 1.0, 1.0, 0.25));
+
+    // how high a bounce from the current height would reach
+    let apex = player_transform.translation.y
+        + tuning.jump_velocity * tuning.jump_velocity / (2.0 * gravity);
+    gizmos.line_2d(
+        Vec2::new(player_transform.translation.x - 24.0, apex),
+        Vec2::new(player_transform.translation.x + 24.0, apex),
+        Color::srgba(1.0, 1.0, 1.0, 0.15),
+    );
+}
@@ -0,0 +1,65 @@
+//! Blitz mode: climb as high as possible before the clock runs out.
+
+use bevy::prelude::*;
+
+use super::{GameConfig, GameMode, GameSet, LastRunEnd, RunEnd};
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<BlitzClock>()
+        .add_event::<TimeRanOut>()
+        .add_systems(OnEnter(Screen::InGame), reset_blitz_clock)
+        .add_systems(
+            FixedUpdate,
+            run_blitz_clock
+                .in_set(GameSet::Damage)
+                .run_if(|config: Res<GameConfig>| config.mode == GameMode::Blitz),
+        )
+        .add_systems(
+            Update,
+            end_run_on_time_up.run_if(in_state(Screen::InGame).and_then(on_event::<TimeRanOut>())),
+        );
+}
+
+/// Time left in a blitz run. It only counts down while the game isn't paused.
+#[derive(Resource, Debug)]
+pub struct BlitzClock(pub Timer);
+impl BlitzClock {
+    pub const SECONDS: f32 = 120.0;
+
+    pub fn seconds_left(&self) -> f32 {
+        self.0.remaining_secs()
+    }
+}
+impl Default for BlitzClock {
+    fn default() -> Self {
+        Self(Timer::from_seconds(Self::SECONDS, TimerMode::Once))
+    }
+}
+
+/// Sent when a blitz run's clock runs out, ending the run.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct TimeRanOut;
+
+fn reset_blitz_clock(mut commands: Commands) {
+    commands.insert_resource(BlitzClock::default());
+}
+
+fn run_blitz_clock(
+    time: Res<Time>,
+    mut clock: ResMut<BlitzClock>,
+    mut time_ran_out: EventWriter<TimeRanOut>,
+) {
+    if clock.0.tick(time.delta()).just_finished() {
+        time_ran_out.send(TimeRanOut);
+        eprintln!("Time's up.");
+    }
+}
+
+fn end_run_on_time_up(
+    mut last_run_end: ResMut<LastRunEnd>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    last_run_end.0 = Some(RunEnd::TimeUp);
+    next_screen.set(Screen::GameOver);
+}
@@ -0,0 +1,105 @@
+//! The camera following the player up.
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use super::{screen_tracking, GameSet, Player, ScreenHeight, Velocity};
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CameraFollow>()
+        .init_resource::<PlayArea>()
+        .add_systems(OnEnter(Screen::InGame), fit_play_area_to_window)
+        .add_systems(
+            Update,
+            fit_play_area_to_window.run_if(on_event::<WindowResized>()),
+        )
+        .add_systems(
+            FixedUpdate,
+            follow_player
+                .in_set(GameSet::Tracking)
+                .after(screen_tracking),
+        );
+}
+
+/// Marks the camera that follows the player.
+/// Other cameras (debug views, a host app's own cameras) are left alone.
+#[derive(Component, Debug)]
+pub struct GameCamera;
+
+/// How the [`GameCamera`] follows the player.
+///
+/// It aims above the highest point reached, shifted towards where the player is heading,
+/// and only moves once that aim leaves a deadzone around its current position.
+#[derive(Resource, Debug, Clone)]
+pub struct CameraFollow {
+    /// How far above the highest point reached the camera centers.
+    pub offset: f32,
+    /// How far the aim can be from the camera before it starts moving.
+    pub deadzone: f32,
+    /// Seconds of vertical velocity to look ahead by.
+    pub look_ahead: f32,
+    /// The most look-ahead can shift the aim, either way.
+    pub max_look_ahead: f32,
+    /// How quickly the camera closes the distance, per second. Higher is snappier.
+    pub smoothing: f32,
+}
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            offset: 250.0,
+            deadzone: 24.0,
+            look_ahead: 0.2,
+            max_look_ahead: 100.0,
+            smoothing: 6.0,
+        }
+    }
+}
+
+fn follow_player(
+    time: Res<Time>,
+    follow: Res<CameraFollow>,
+    screen_height: Res<ScreenHeight>,
+    player_query: Query<&Velocity, With<Player>>,
+    mut camera_query: Query<&mut Transform, With<GameCamera>>,
+) {
+    let look_ahead = player_query.get_single().map_or(0.0, |player_velocity| {
+        (player_velocity.y * follow.look_ahead).clamp(-follow.max_look_ahead, follow.max_look_ahead)
+    });
+    let aim = screen_height.0 + follow.offset + look_ahead;
+    for mut camera_transform in camera_query.iter_mut() {
+        let camera_y = camera_transform.translation.y;
+        let outside_deadzone =
+            aim - aim.clamp(camera_y - follow.deadzone, camera_y + follow.deadzone);
+        // exponential smoothing, independent of the step length
+        let blend = 1.0 - (-follow.smoothing * time.delta_seconds()).exp();
+        camera_transform.translation.y += outside_deadzone * blend;
+    }
+}
+
+/// The horizontal extent of the world the [`GameCamera`] shows, centered on `x = 0`.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PlayArea {
+    pub half_width: f32,
+}
+impl Default for PlayArea {
+    fn default() -> Self {
+        // until there is a window to measure
+        Self { half_width: 128.0 }
+    }
+}
+
+/// Assumes the camera projection scales with the window size, as a default 2D camera does.
+fn fit_play_area_to_window(
+    mut play_area: ResMut<PlayArea>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<&OrthographicProjection, With<GameCamera>>,
+) {
+    let (Ok(window), Ok(projection)) = (window_query.get_single(), camera_query.get_single())
+    else {
+        return;
+    };
+    play_area.half_width = window.width() / 2.0 * projection.scale;
+}
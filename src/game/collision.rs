@@ -0,0 +1,283 @@
+//! Overlap tests between the simple shapes entities collide as.
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, fit_colliders_to_images);
+}
+
+/// The shape an entity collides as, centered on its translation.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Deref)]
+pub struct Collider(pub Shape);
+
+/// Gives the entity a [`Collider`] sized from its sprite's image, once the image has loaded.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ColliderFromImage {
+    /// Builds the shape from the size it should fill.
+    pub shape: fn(Vec2) -> Shape,
+    /// Fraction of the image size the collider covers, for art that doesn't fill its image.
+    pub shrink: f32,
+}
+impl ColliderFromImage {
+    pub fn aabb() -> Self {
+        Self {
+            shape: Shape::aabb,
+            shrink: 1.0,
+        }
+    }
+
+    pub fn circle() -> Self {
+        Self {
+            shape: Shape::circle,
+            shrink: 1.0,
+        }
+    }
+
+    pub fn with_shrink(self, shrink: f32) -> Self {
+        Self { shrink, ..self }
+    }
+}
+
+fn fit_colliders_to_images(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    query: Query<(Entity, &Handle<Image>, &ColliderFromImage)>,
+) {
+    for (entity, image_handle, from_image) in query.iter() {
+        let Some(image) = images.get(image_handle) else {
+            continue;
+        };
+        let shape = (from_image.shape)(image.size_f32() * from_image.shrink);
+        commands
+            .entity(entity)
+            .remove::<ColliderFromImage>()
+            .insert(Collider(shape));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// An axis-aligned box.
+    Aabb {
+        half_extents: Vec2,
+    },
+    Circle {
+        radius: f32,
+    },
+    /// A vertical capsule: a segment reaching `half_height` above and below the center,
+    /// padded by `radius` on all sides.
+    Capsule {
+        half_height: f32,
+        radius: f32,
+    },
+}
+impl Shape {
+    /// An axis-aligned box of the given full size.
+    pub fn aabb(size: Vec2) -> Self {
+        Self::Aabb {
+            half_extents: size / 2.0,
+        }
+    }
+
+    /// The largest circle fitting in a box of the given size.
+    pub fn circle(size: Vec2) -> Self {
+        Self::Circle {
+            radius: size.min_element() / 2.0,
+        }
+    }
+
+    /// Half the size of the smallest axis-aligned box containing the shape.
+    pub fn half_extents(self) -> Vec2 {
+        let (core, radius) = self.rounded_box();
+        core + radius
+    }
+
+    pub fn overlaps(self, position: Vec2, other: Shape, other_position: Vec2) -> bool {
+        // Every supported shape is a box (possibly flat or a point) grown by a radius, and the
+        // Minkowski sum of two of those is again a rounded box. So it's enough to measure the
+        // gap between the cores and compare it to the combined radius.
+        let (core, radius) = self.rounded_box();
+        let (other_core, other_radius) = other.rounded_box();
+        let gap = ((position - other_position).abs() - (core + other_core)).max(Vec2::ZERO);
+        gap.length_squared() <= (radius + other_radius).powi(2)
+    }
+
+    /// When the shape lands on top of `other` while moving from `from` to `to`, as a fraction of
+    /// the movement: its bottom crosses the top of `other` going down, and they overlap
+    /// horizontally at that moment. Coming from inside or below doesn't count, so `other` acts
+    /// as a one-way platform.
+    pub fn landing_time(
+        self,
+        from: Vec2,
+        to: Vec2,
+        other: Shape,
+        other_position: Vec2,
+    ) -> Option<f32> {
+        let half_extents = self.half_extents();
+        let other_half_extents = other.half_extents();
+        let top = other_position.y + other_half_extents.y;
+        let from_bottom = from.y - half_extents.y;
+        let to_bottom = to.y - half_extents.y;
+        if from_bottom < top || to_bottom > top || from_bottom == to_bottom {
+            return None;
+        }
+        // checking where the crossing happens instead of where the movement ended means fast
+        // falls can't skip past the surface
+        let time = (from_bottom - top) / (from_bottom - to_bottom);
+        let crossing = from.lerp(to, time);
+        ((crossing.x - other_position.x).abs() <= half_extents.x + other_half_extents.x)
+            .then_some(time)
+    }
+
+    /// How far the shape at `position` has to move up to rest on top of `other`, if it sank
+    /// into it from above: they overlap horizontally, and its bottom is below the top of
+    /// `other` while its center is still above it. This catches what [`Self::landing_time`]
+    /// can't, like a platform rising into the shape.
+    pub fn depenetration(self, position: Vec2, other: Shape, other_position: Vec2) -> Option<f32> {
+        let half_extents = self.half_extents();
+        let other_half_extents = other.half_extents();
+        let top = other_position.y + other_half_extents.y;
+        let depth = top - (position.y - half_extents.y);
+        (depth > 0.0
+            && position.y > top
+            && (position.x - other_position.x).abs() <= half_extents.x + other_half_extents.x)
+            .then_some(depth)
+    }
+
+    /// Half extents of the shape's core box, and the radius it is grown by.
+    fn rounded_box(self) -> (Vec2, f32) {
+        match self {
+            Shape::Aabb { half_extents } => (half_extents, 0.0),
+            Shape::Circle { radius } => (Vec2::ZERO, radius),
+            Shape::Capsule {
+                half_height,
+                radius,
+            } => (Vec2::new(0.0, half_height), radius),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIT_BOX: Shape = Shape::Aabb {
+        half_extents: Vec2::splat(0.5),
+    };
+    const UNIT_CIRCLE: Shape = Shape::Circle { radius: 0.5 };
+    const CAPSULE: Shape = Shape::Capsule {
+        half_height: 1.0,
+        radius: 0.5,
+    };
+
+    #[test]
+    fn boxes_overlap_by_half_extents() {
+        assert!(UNIT_BOX.overlaps(Vec2::ZERO, UNIT_BOX, Vec2::new(0.9, 0.0)));
+        assert!(UNIT_BOX.overlaps(Vec2::ZERO, UNIT_BOX, Vec2::new(0.9, 0.9)));
+        assert!(!UNIT_BOX.overlaps(Vec2::ZERO, UNIT_BOX, Vec2::new(1.1, 0.0)));
+    }
+
+    #[test]
+    fn boxes_need_to_overlap_on_both_axes() {
+        // the old test accepted either axis
+        assert!(!UNIT_BOX.overlaps(Vec2::ZERO, UNIT_BOX, Vec2::new(0.0, 5.0)));
+        assert!(!UNIT_BOX.overlaps(Vec2::ZERO, UNIT_BOX, Vec2::new(5.0, 0.0)));
+    }
+
+    #[test]
+    fn box_corner_misses_circle() {
+        // inside the box's bounding square around the circle, but past the rounded corner
+        let corner_gap = Vec2::splat(0.5 + 0.5 * std::f32::consts::FRAC_1_SQRT_2 + 0.05);
+        assert!(!UNIT_BOX.overlaps(Vec2::ZERO, UNIT_CIRCLE, corner_gap));
+        assert!(UNIT_BOX.overlaps(Vec2::ZERO, UNIT_CIRCLE, Vec2::new(0.95, 0.0)));
+    }
+
+    #[test]
+    fn circles_overlap_by_radius() {
+        assert!(UNIT_CIRCLE.overlaps(Vec2::ZERO, UNIT_CIRCLE, Vec2::new(0.6, 0.6)));
+        assert!(!UNIT_CIRCLE.overlaps(Vec2::ZERO, UNIT_CIRCLE, Vec2::new(0.75, 0.75)));
+    }
+
+    #[test]
+    fn capsule_reaches_further_vertically() {
+        assert!(CAPSULE.overlaps(Vec2::ZERO, UNIT_CIRCLE, Vec2::new(0.0, 1.9)));
+        assert!(!CAPSULE.overlaps(Vec2::ZERO, UNIT_CIRCLE, Vec2::new(1.1, 0.0)));
+        assert!(CAPSULE.overlaps(Vec2::ZERO, CAPSULE, Vec2::new(0.5, 2.5)));
+    }
+
+    #[test]
+    fn lands_when_crossing_the_top_going_down() {
+        let platform = Vec2::ZERO;
+        assert_eq!(
+            UNIT_BOX.landing_time(Vec2::new(0.0, 1.5), Vec2::new(0.0, 0.5), UNIT_BOX, platform),
+            Some(0.5)
+        );
+        // from far above to far below in a single step
+        assert_eq!(
+            UNIT_BOX.landing_time(
+                Vec2::new(0.0, 5.0),
+                Vec2::new(0.0, -5.0),
+                UNIT_BOX,
+                platform
+            ),
+            Some(0.4)
+        );
+    }
+
+    #[test]
+    fn passes_through_from_inside_or_below() {
+        let platform = Vec2::ZERO;
+        assert!(UNIT_BOX
+            .landing_time(Vec2::new(0.0, 0.5), Vec2::new(0.0, 0.2), UNIT_BOX, platform)
+            .is_none());
+        assert!(UNIT_BOX
+            .landing_time(
+                Vec2::new(0.0, -2.0),
+                Vec2::new(0.0, 2.0),
+                UNIT_BOX,
+                platform
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn landing_checks_horizontal_overlap_at_the_crossing() {
+        // ends up above the platform, but was beside it when passing its top
+        assert!(UNIT_BOX
+            .landing_time(
+                Vec2::new(-9.0, 2.0),
+                Vec2::new(0.0, 0.8),
+                UNIT_BOX,
+                Vec2::ZERO
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn depenetrates_up_out_of_the_top() {
+        let platform = Vec2::ZERO;
+        assert_eq!(
+            UNIT_BOX.depenetration(Vec2::new(0.25, 0.75), UNIT_BOX, platform),
+            Some(0.25)
+        );
+        // resting on top, below the top, or beside the platform
+        assert!(UNIT_BOX
+            .depenetration(Vec2::new(0.0, 1.0), UNIT_BOX, platform)
+            .is_none());
+        assert!(UNIT_BOX
+            .depenetration(Vec2::new(0.0, 0.25), UNIT_BOX, platform)
+            .is_none());
+        assert!(UNIT_BOX
+            .depenetration(Vec2::new(1.5, 0.75), UNIT_BOX, platform)
+            .is_none());
+    }
+
+    #[test]
+    fn half_extents_include_radius() {
+        assert_eq!(CAPSULE.half_extents(), Vec2::new(0.5, 1.5));
+        assert_eq!(
+            Shape::aabb(Vec2::new(4.0, 2.0)).half_extents(),
+            Vec2::new(2.0, 1.0)
+        );
+    }
+}
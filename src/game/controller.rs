@@ -0,0 +1,227 @@
+//! Moving entities by their velocity, and landing them on the platforms they fall onto.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{
+    platform::PhasedOut, Collider, GameSet, GravityScale, Platform, SpeedLimits, Velocity,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(FixedUpdate, step_physics.in_set(GameSet::Movement));
+}
+
+/// Makes an entity with a [`Velocity`] and a [`Collider`] collide with platforms as it moves,
+/// and reports what it touched. Platforms are one-way: they are only landed on from above, and
+/// anything moving up or sideways passes through them.
+///
+/// Entities with a velocity but no controller just move, like debris that falls through
+/// everything.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct KinematicController {
+    /// Whether the last physics step ended with the entity on a platform.
+    /// What it landed on is in its [`LastContact`].
+    pub grounded: bool,
+}
+
+/// The last platform a [`KinematicController`] landed on, kept after leaving it so landing
+/// effects and timing windows don't each have to work it out again.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct LastContact {
+    pub platform: Entity,
+    pub surface: Surface,
+    /// Where the entity was when it landed.
+    pub position: Vec2,
+    /// Fixed time elapsed when it landed.
+    pub at: Duration,
+}
+
+/// What a platform is like to land on. Platforms without one are [`Surface::Solid`].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    #[default]
+    Solid,
+    /// A spring, which bounces higher through its [`Bounciness`](super::Bounciness).
+    Spring,
+    /// Slippery, making the player hard to steer for a while, see
+    /// [`SurfaceModifier`](super::SurfaceModifier).
+    Ice,
+}
+
+/// Moves everything with a [`Velocity`] for one fixed step, within its [`SpeedLimits`].
+pub(super) fn step_physics(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut physics_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            Option<&SpeedLimits>,
+            Option<&GravityScale>,
+            Option<(&Collider, &mut KinematicController)>,
+        ),
+        Without<Platform>,
+    >,
+    platform_query: Query<
+        (Entity, &Transform, &Collider, Option<&Surface>),
+        (With<Platform>, Without<PhasedOut>),
+    >,
+) {
+    for (entity, mut transform, mut velocity, speed_limits, gravity_scale, controlled) in
+        physics_query.iter_mut()
+    {
+        if let Some(speed_limits) = speed_limits {
+            let gravity_scale = gravity_scale.map_or(1.0, |scale| scale.0);
+            velocity.0 = speed_limits.clamp(velocity.0, gravity_scale);
+        }
+        let from = transform.translation.truncate();
+        let to = from + velocity.0 * time.delta_seconds();
+        let Some((collider, mut controller)) = controlled else {
+            transform.translation = to.extend(transform.translation.z);
+            continue;
+        };
+        // sweeping the whole step finds landings no matter how far it moved, and stopping at
+        // the surface makes every bounce start from the same height
+        let landing = (velocity.y <= 0.0)
+            .then(|| {
+                platform_query
+                    .iter()
+                    .filter_map(
+                        |(platform, platform_transform, platform_collider, surface)| {
+                            let time = collider.landing_time(
+                                from,
+                                to,
+                                **platform_collider,
+                                platform_transform.translation.truncate(),
+                            )?;
+                            Some((time, platform, surface.copied().unwrap_or_default()))
+                        },
+                    )
+                    .min_by(|(time, ..), (other_time, ..)| time.total_cmp(other_time))
+            })
+            .flatten();
+        let mut position = from.lerp(to, landing.map_or(1.0, |(time, ..)| time));
+        // an entity that ends up sunk into a platform's top (say, one that rose into it) is
+        // pushed back out onto it, instead of falling through from inside
+        let landing = landing
+            .map(|(_, platform, surface)| (platform, surface))
+            .or_else(|| {
+                if velocity.y > 0.0 {
+                    return None;
+                }
+                let (depth, platform, surface) = platform_query
+                    .iter()
+                    .filter_map(
+                        |(platform, platform_transform, platform_collider, surface)| {
+                            let depth = collider.depenetration(
+                                position,
+                                **platform_collider,
+                                platform_transform.translation.truncate(),
+                            )?;
+                            Some((depth, platform, surface.copied().unwrap_or_default()))
+                        },
+                    )
+                    .max_by(|(depth, ..), (other_depth, ..)| depth.total_cmp(other_depth))?;
+                position.y += depth;
+                Some((platform, surface))
+            });
+        controller.grounded = landing.is_some();
+        if let Some((platform, surface)) = landing {
+            commands.entity(entity).insert(LastContact {
+                platform,
+                surface,
+                position,
+                at: time.elapsed(),
+            });
+        }
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::game::Shape;
+
+    /// A world with a fixed step's time passed, and a platform whose top is at a height of 8.
+    fn world_with_platform() -> (World, Entity) {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs_f64(1.0 / 64.0));
+        world.insert_resource(time);
+        let platform = world
+            .spawn((
+                Platform,
+                Transform::default(),
+                Collider(Shape::aabb(Vec2::new(64.0, 16.0))),
+            ))
+            .id();
+        (world, platform)
+    }
+
+    /// Spawns a 16 by 16 box at `position` and moves it for one step.
+    fn step(world: &mut World, position: Vec2, velocity: Vec2) -> Entity {
+        let entity = world
+            .spawn((
+                Transform::from_translation(position.extend(0.0)),
+                Velocity(velocity),
+                Collider(Shape::aabb(Vec2::splat(16.0))),
+                KinematicController::default(),
+            ))
+            .id();
+        world.run_system_once(step_physics);
+        entity
+    }
+
+    fn assert_landed_on(world: &World, entity: Entity, platform: Entity) {
+        let position = world
+            .get::<Transform>(entity)
+            .unwrap()
+            .translation
+            .truncate();
+        assert_eq!(position, Vec2::new(0.0, 16.0));
+        assert!(world.get::<KinematicController>(entity).unwrap().grounded);
+        let contact = world.get::<LastContact>(entity).unwrap();
+        assert_eq!(contact.platform, platform);
+        assert_eq!(contact.position, position);
+    }
+
+    #[test]
+    fn lands_on_top() {
+        let (mut world, platform) = world_with_platform();
+        let entity = step(&mut world, Vec2::new(0.0, 20.0), Vec2::new(0.0, -600.0));
+        assert_landed_on(&world, entity, platform);
+    }
+
+    #[test]
+    fn fast_falls_dont_tunnel() {
+        let (mut world, platform) = world_with_platform();
+        // far past the platform within the step
+        let entity = step(
+            &mut world,
+            Vec2::new(0.0, 100.0),
+            Vec2::new(0.0, -100_000.0),
+        );
+        assert_landed_on(&world, entity, platform);
+    }
+
+    #[test]
+    fn overlaps_are_pushed_out_on_top() {
+        let (mut world, platform) = world_with_platform();
+        let entity = step(&mut world, Vec2::new(0.0, 12.0), Vec2::ZERO);
+        assert_landed_on(&world, entity, platform);
+    }
+
+    #[test]
+    fn rising_passes_through() {
+        let (mut world, _) = world_with_platform();
+        let entity = step(&mut world, Vec2::new(0.0, 12.0), Vec2::new(0.0, 600.0));
+        assert!(world.get::<Transform>(entity).unwrap().translation.y > 12.0);
+        assert!(!world.get::<KinematicController>(entity).unwrap().grounded);
+        assert!(world.get::<LastContact>(entity).is_none());
+    }
+}
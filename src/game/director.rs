@@ -0,0 +1,281 @@
+//! A pacing director that nudges hazard spawn chances to keep the run at a target tension.
+//!
+//! Every 100 height it looks at how quickly the player has been climbing and how many hazards
+//! they nearly touched, then raises or lowers the spike and enemy chances within fixed bounds.
+//! The climb rate is averaged over the last few hundred height, since bounces climb in bursts,
+//! and compared to the steady pace of landing on each next platform up (see
+//! [`GameTuning::platform_climb_seconds`]).
+//! Seeded runs keep the initial chances, so everyone playing a seed meets the same hazards.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use super::{DamageSource, GameConfig, GameSet, GameTuning, Player, ScreenHeight};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DirectorConfig>()
+        .init_resource::<PacingDirector>()
+        .add_event::<NearMiss>()
+        .add_systems(
+            FixedUpdate,
+            (
+                count_near_misses,
+                update_pacing.run_if(|config: Res<GameConfig>| config.run_seed().is_none()),
+            )
+                .chain()
+                .in_set(GameSet::Tracking)
+                .after(super::update_score),
+        );
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct DirectorConfig {
+    /// How far the climb rate can stray from the steady pace, as a fraction of it, before the
+    /// chances move. Faster (with few near misses) means the run is too easy, slower too hard.
+    pub pace_tolerance: f32,
+    /// How many of the last checkpoints the climb rate is averaged over.
+    pub pace_window: usize,
+    /// Hazards passing within this distance of the player count as near misses.
+    pub near_miss_distance: f32,
+    /// This many near misses between evaluations means the player is under pressure.
+    pub near_miss_threshold: u32,
+    /// How far a single evaluation moves each spawn chance.
+    pub step: f64,
+    pub spike_chance: ChanceBounds,
+    pub enemy_chance: ChanceBounds,
+}
+impl Default for DirectorConfig {
+    fn default() -> Self {
+        Self {
+            pace_tolerance: 0.25,
+            pace_window: 8,
+            near_miss_distance: 48.0,
+            near_miss_threshold: 2,
+            step: 0.02,
+            spike_chance: ChanceBounds {
+                min: 0.1,
+                initial: 1.0 / 4.0,
+                max: 0.5,
+            },
+            enemy_chance: ChanceBounds {
+                min: 0.05,
+                initial: 1.0 / 7.0,
+                max: 0.35,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChanceBounds {
+    pub min: f64,
+    pub initial: f64,
+    pub max: f64,
+}
+impl ChanceBounds {
+    /// Keeps a chance within the bounds, and the bounds within what a chance can be,
+    /// so a mistuned config can't push spawning past certainty or panic the RNG.
+    pub fn clamp(&self, chance: f64) -> f64 {
+        let min = self.min.clamp(0.0, 1.0);
+        let max = self.max.clamp(min, 1.0);
+        chance.clamp(min, max)
+    }
+}
+
+/// Current spawn chances, read by the platform spawner.
+#[derive(Resource, Debug)]
+pub struct PacingDirector {
+    /// Chance for a platform to get spikes.
+    pub spike_chance: f64,
+    /// Chance for an enemy to patrol above a platform.
+    pub enemy_chance: f64,
+    next_checkpoint: f32,
+    /// Time spent climbing since the last checkpoint. Pauses don't count towards it.
+    seconds_since_checkpoint: f32,
+    /// How long each of the last [`DirectorConfig::pace_window`] checkpoints took to reach.
+    recent_seconds_per_100: VecDeque<f32>,
+    near_misses: u32,
+}
+impl PacingDirector {
+    /// A director at the configured initial chances, as a run starts out.
+    pub fn new(config: &DirectorConfig, tuning: &GameTuning) -> Self {
+        // the run opens with a launch straight up to its apex, which says nothing about how
+        // quickly the player climbs, so the first checkpoint is above it
+        let launch_apex =
+            tuning.spawn_velocity.y.max(0.0).powi(2) / (2.0 * tuning.gravity.max(1.0));
+        Self {
+            spike_chance: config.spike_chance.clamp(config.spike_chance.initial),
+            enemy_chance: config.enemy_chance.clamp(config.enemy_chance.initial),
+            next_checkpoint: (launch_apex / 100.0).floor() * 100.0 + 100.0,
+            seconds_since_checkpoint: 0.0,
+            recent_seconds_per_100: VecDeque::new(),
+            near_misses: 0,
+        }
+    }
+
+    /// How far spawning has been turned up, from 0 at the easiest to 1 at the hardest.
+    pub fn difficulty(&self, config: &DirectorConfig) -> f32 {
+        let bounds = config.spike_chance;
+        let range = bounds.max - bounds.min;
+        if range <= 0.0 {
+            return 0.0;
+        }
+        ((self.spike_chance - bounds.min) / range).clamp(0.0, 1.0) as f32
+    }
+
+    /// Moves the next checkpoint along with the world, see [`super::origin`].
+    pub(super) fn rebase(&mut self, shift: f32) {
+        self.next_checkpoint -= shift;
+    }
+}
+impl FromWorld for PacingDirector {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(
+            world.resource::<DirectorConfig>(),
+            world.resource::<GameTuning>(),
+        )
+    }
+}
+
+/// Sent when a hazard passes close to the player without touching them.
+#[derive(Event, Debug)]
+pub struct NearMiss {
+    pub position: Vec2,
+    pub source: DamageSource,
+}
+
+/// Marks hazards that already counted as a near miss.
+#[derive(Component)]
+struct NearMissed;
+
+fn count_near_misses(
+    mut commands: Commands,
+    config: Res<DirectorConfig>,
+    mut director: ResMut<PacingDirector>,
+    mut near_missed: EventWriter<NearMiss>,
+    player_query: Query<&Transform, With<Player>>,
+    hazard_query: Query<(Entity, &Transform, &DamageSource), Without<NearMissed>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (hazard_entity, hazard_transform, &source) in hazard_query.iter() {
+        let hazard_pos = hazard_transform.translation.truncate();
+        if player_pos.distance(hazard_pos) <= config.near_miss_distance {
+            commands.entity(hazard_entity).insert(NearMissed);
+            director.near_misses += 1;
+            near_missed.send(NearMiss {
+                position: hazard_pos,
+                source,
+            });
+        }
+    }
+}
+
+fn update_pacing(
+    time: Res<Time>,
+    screen_height: Res<ScreenHeight>,
+    config: Res<DirectorConfig>,
+    tuning: Res<GameTuning>,
+    mut director: ResMut<PacingDirector>,
+) {
+    director.seconds_since_checkpoint += time.delta_seconds();
+    if screen_height.0 < director.next_checkpoint {
+        return;
+    }
+    // a single jump can cross several checkpoints at once
+    let crossed = ((screen_height.0 - director.next_checkpoint) / 100.0).floor() + 1.0;
+    let seconds_per_100 = std::mem::take(&mut director.seconds_since_checkpoint) / crossed;
+    director.next_checkpoint += crossed * 100.0;
+    for _ in 0..(crossed as usize).min(config.pace_window) {
+        director.recent_seconds_per_100.push_back(seconds_per_100);
+    }
+    while director.recent_seconds_per_100.len() > config.pace_window {
+        director.recent_seconds_per_100.pop_front();
+    }
+
+    let near_misses = std::mem::take(&mut director.near_misses);
+    // a few checkpoints alone would mostly measure where in a bounce they happened to fall
+    if director.recent_seconds_per_100.len() < config.pace_window.max(1) {
+        return;
+    }
+    let seconds_per_100 = director.recent_seconds_per_100.iter().sum::<f32>()
+        / director.recent_seconds_per_100.len() as f32;
+    let steady_seconds_per_100 =
+        tuning.platform_climb_seconds() * 100.0 / tuning.platform_spacing();
+    let step = if near_misses >= config.near_miss_threshold
+        || seconds_per_100 > steady_seconds_per_100 * (1.0 + config.pace_tolerance)
+    {
+        -config.step
+    } else if seconds_per_100 < steady_seconds_per_100 * (1.0 - config.pace_tolerance) {
+        config.step
+    } else {
+        0.0
+    };
+    director.spike_chance = config.spike_chance.clamp(director.spike_chance + step);
+    director.enemy_chance = config.enemy_chance.clamp(director.enemy_chance + step);
+    eprintln!(
+        "Director: {seconds_per_100:.2}s per 100 height (steady is {steady_seconds_per_100:.2}s), {near_misses} near misses -> spike chance {:.2}, enemy chance {:.2}",
+        director.spike_chance, director.enemy_chance
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    /// Bounces up one platform at a time for `seconds`, the way the player would without any
+    /// hazards in the way, and returns the lowest and highest spike chance along the way.
+    fn climb_steadily(tuning: GameTuning, seconds: f32) -> (f64, f64) {
+        const STEP_SECONDS: f32 = 1.0 / 64.0;
+        let mut world = World::new();
+        world.insert_resource(tuning.clone());
+        world.init_resource::<DirectorConfig>();
+        world.init_resource::<PacingDirector>();
+        world.init_resource::<ScreenHeight>();
+        world.init_resource::<Time>();
+
+        let (mut y, mut velocity, mut platform) = (0.0, tuning.spawn_velocity.y, 0.0);
+        let initial = world.resource::<PacingDirector>().spike_chance;
+        let (mut lowest, mut highest) = (initial, initial);
+        for _ in 0..(seconds / STEP_SECONDS) as usize {
+            velocity -= tuning.gravity * STEP_SECONDS;
+            y += velocity * STEP_SECONDS;
+            let next_platform = platform + tuning.platform_spacing();
+            if velocity < 0.0 && y <= next_platform {
+                (y, velocity, platform) = (next_platform, tuning.jump_velocity, next_platform);
+            }
+            let mut screen_height = world.resource_mut::<ScreenHeight>();
+            screen_height.0 = screen_height.0.max(y);
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(STEP_SECONDS));
+            world.run_system_once(update_pacing);
+            let chance = world.resource::<PacingDirector>().spike_chance;
+            lowest = lowest.min(chance);
+            highest = highest.max(chance);
+        }
+        (lowest, highest)
+    }
+
+    #[test]
+    fn steady_climbing_keeps_chances_level() {
+        let initial = DirectorConfig::default().spike_chance.initial;
+        for tuning in [
+            GameTuning::default(),
+            GameTuning {
+                platform_distance: 300.0,
+                ..default()
+            },
+        ] {
+            let (lowest, highest) = climb_steadily(tuning, 300.0);
+            assert_eq!((lowest, highest), (initial, initial));
+        }
+    }
+}
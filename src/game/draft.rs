@@ -0,0 +1,374 @@
+//! Drafting upgrades: a few are drawn from a weighted pool, and the player picks one of them
+//! or skips, with a [`DraftChoice`]. Upgrades are defined in `assets/marathon.upgrades.ron`.
+//!
+//! Offering a draft pauses the run in [`Pause::Intermission`] until the pick is made.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{GameRng, GameTuning, GravityScale, Player, Score, SpeedLimits};
+use crate::{
+    gamepad::gamepad_just_pressed,
+    input::Action,
+    screen::{Pause, Screen},
+    touch::TouchInput,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<UpgradeCatalog>()
+        .register_asset_loader(UpgradeCatalogLoader)
+        .init_resource::<UpgradeCatalog>()
+        .init_resource::<Draft>()
+        .add_event::<DraftChoice>()
+        .add_systems(Startup, load_upgrade_catalog)
+        .add_systems(OnEnter(Screen::InGame), reset_draft)
+        .add_systems(
+            Update,
+            (
+                apply_upgrade_catalog.run_if(on_event::<AssetEvent<UpgradeCatalog>>()),
+                (
+                    (
+                        choose(DraftChoice::Pick(0)).run_if(
+                            input_just_pressed(KeyCode::Digit1)
+                                .or_else(gamepad_just_pressed(GamepadButtonType::West)),
+                        ),
+                        choose(DraftChoice::Pick(1)).run_if(
+                            input_just_pressed(KeyCode::Digit2)
+                                .or_else(gamepad_just_pressed(GamepadButtonType::North)),
+                        ),
+                        choose(DraftChoice::Pick(2)).run_if(
+                            input_just_pressed(KeyCode::Digit3)
+                                .or_else(gamepad_just_pressed(GamepadButtonType::East)),
+                        ),
+                        choose(DraftChoice::Skip).run_if(confirmed_without_touch),
+                    ),
+                    apply_draft_choice.run_if(on_event::<DraftChoice>()),
+                )
+                    .chain()
+                    .run_if(in_state(Pause::Intermission)),
+            ),
+        );
+}
+
+/// Every upgrade that can be drafted. Starts out with the built-in ones, which are replaced
+/// once the file has loaded.
+#[derive(Asset, Resource, TypePath, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpgradeCatalog {
+    pub upgrades: Vec<UpgradeDef>,
+}
+impl Default for UpgradeCatalog {
+    fn default() -> Self {
+        let upgrade = |id: &str, name: &str, description: &str, effects| UpgradeDef {
+            id: id.into(),
+            name: name.into(),
+            description: description.into(),
+            weight: 1.0,
+            max_picks: None,
+            effects,
+            excludes: Vec::new(),
+            synergies: Vec::new(),
+        };
+        Self {
+            upgrades: vec![
+                UpgradeDef {
+                    synergies: vec![Synergy {
+                        with: "featherweight".into(),
+                        weight: 2.0,
+                    }],
+                    ..upgrade(
+                        "floaty",
+                        "Floaty",
+                        "10% less gravity",
+                        vec![UpgradeEffect::Gravity(0.9)],
+                    )
+                },
+                upgrade(
+                    "nimble",
+                    "Nimble",
+                    "15% faster sideways",
+                    vec![UpgradeEffect::HorizontalSpeed(1.15)],
+                ),
+                upgrade(
+                    "featherweight",
+                    "Featherweight",
+                    "15% slower falls",
+                    vec![UpgradeEffect::FallSpeed(0.85)],
+                ),
+                UpgradeDef {
+                    weight: 0.5,
+                    ..upgrade(
+                        "bonus",
+                        "Bonus",
+                        "500 points",
+                        vec![UpgradeEffect::Bonus(500)],
+                    )
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpgradeDef {
+    /// What other upgrades refer to this one by.
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// How likely it is to be drawn, relative to the others.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// How many times it can be picked in a run. Unlimited if missing.
+    #[serde(default)]
+    pub max_picks: Option<u32>,
+    pub effects: Vec<UpgradeEffect>,
+    /// Upgrades that can't be drafted once this one is picked, and the other way around.
+    #[serde(default)]
+    pub excludes: Vec<String>,
+    /// Upgrades that make this one more likely to be drawn once picked.
+    #[serde(default)]
+    pub synergies: Vec<Synergy>,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Synergy {
+    /// The upgrade that has to be picked.
+    pub with: String,
+    /// What the weight is multiplied by for every time it was picked.
+    pub weight: f64,
+}
+
+/// What picking an upgrade does, for the rest of the run.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum UpgradeEffect {
+    /// Multiplies the player's [`GravityScale`].
+    Gravity(f32),
+    /// Multiplies the player's top sideways speed.
+    HorizontalSpeed(f32),
+    /// Multiplies the player's top falling speed.
+    FallSpeed(f32),
+    /// Points on the spot.
+    Bonus(u32),
+}
+impl UpgradeEffect {
+    fn apply(self, score: &mut Score, modifiers: &mut UpgradeModifiers) {
+        match self {
+            UpgradeEffect::Gravity(factor) => modifiers.gravity *= factor,
+            UpgradeEffect::HorizontalSpeed(factor) => modifiers.horizontal_speed *= factor,
+            UpgradeEffect::FallSpeed(factor) => modifiers.fall_speed *= factor,
+            UpgradeEffect::Bonus(points) => score.bonus += points,
+        }
+    }
+}
+
+/// What the run's picked upgrades multiply the player's movement by. Kept apart from the
+/// tuning, so reloading it doesn't undo them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UpgradeModifiers {
+    /// The player's [`GravityScale`].
+    pub gravity: f32,
+    pub horizontal_speed: f32,
+    pub fall_speed: f32,
+}
+impl Default for UpgradeModifiers {
+    fn default() -> Self {
+        Self {
+            gravity: 1.0,
+            horizontal_speed: 1.0,
+            fall_speed: 1.0,
+        }
+    }
+}
+impl UpgradeModifiers {
+    /// The tuning's [`GameTuning::player_speed_limits`], with the upgrades on top.
+    pub fn speed_limits(&self, tuning: &GameTuning) -> SpeedLimits {
+        let limits = tuning.player_speed_limits();
+        SpeedLimits {
+            horizontal: limits.horizontal * self.horizontal_speed,
+            fall: limits.fall * self.fall_speed,
+        }
+    }
+}
+
+/// The run's draft: what is on offer right now, and what was picked before.
+#[derive(Resource, Debug, Default)]
+pub struct Draft {
+    /// What the current draft offers. Empty outside of one.
+    pub offered: Vec<UpgradeDef>,
+    /// Ids of the upgrades picked this run, in order.
+    pub picked: Vec<String>,
+    /// What the upgrades picked this run add up to.
+    pub modifiers: UpgradeModifiers,
+}
+impl Draft {
+    /// How many upgrades a draft offers, at most.
+    pub const OFFERED: usize = 3;
+
+    /// Draws upgrades to offer from the catalog. Returns whether there was anything left to
+    /// offer; if not, there is no draft.
+    pub fn offer(&mut self, catalog: &UpgradeCatalog, rng: &mut impl Rng) -> bool {
+        let picks = |id: &str| self.picked.iter().filter(|picked| *picked == id).count();
+        let available: Vec<_> = catalog
+            .upgrades
+            .iter()
+            .filter(|upgrade| {
+                upgrade
+                    .max_picks
+                    .is_none_or(|max| picks(&upgrade.id) < max as usize)
+            })
+            .filter(|upgrade| {
+                !catalog.upgrades.iter().any(|other| {
+                    picks(&other.id) > 0
+                        && (upgrade.excludes.contains(&other.id)
+                            || other.excludes.contains(&upgrade.id))
+                })
+            })
+            .map(|upgrade| {
+                let weight = upgrade
+                    .synergies
+                    .iter()
+                    .map(|synergy| synergy.weight.max(0.0).powi(picks(&synergy.with) as i32))
+                    .product::<f64>()
+                    * upgrade.weight.max(0.0);
+                (upgrade, weight)
+            })
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect();
+        self.offered = available
+            .choose_multiple_weighted(rng, Self::OFFERED, |&(_, weight)| weight)
+            .map_or_else(
+                |_| Vec::new(),
+                |offered| offered.map(|&(upgrade, _)| upgrade.clone()).collect(),
+            );
+        !self.offered.is_empty()
+    }
+}
+
+/// Sent to end the draft, by picking one of the upgrades on offer or none of them. Keys and
+/// gamepad buttons send it here, tapping the draft overlay sends it from the UI.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftChoice {
+    /// The upgrade at this index of [`Draft::offered`].
+    Pick(usize),
+    Skip,
+}
+
+#[derive(Default)]
+struct UpgradeCatalogLoader;
+impl AssetLoader for UpgradeCatalogLoader {
+    type Asset = UpgradeCatalog;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<UpgradeCatalog, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        // the tuning loader already takes plain `.ron`
+        &["upgrades.ron"]
+    }
+}
+
+/// Keeps the file loaded, so changes to it are picked up.
+#[derive(Resource, Debug)]
+struct UpgradeCatalogHandle(Handle<UpgradeCatalog>);
+
+fn load_upgrade_catalog(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(UpgradeCatalogHandle(
+        asset_server.load("marathon.upgrades.ron"),
+    ));
+}
+
+fn apply_upgrade_catalog(
+    mut events: EventReader<AssetEvent<UpgradeCatalog>>,
+    handle: Res<UpgradeCatalogHandle>,
+    assets: Res<Assets<UpgradeCatalog>>,
+    mut catalog: ResMut<UpgradeCatalog>,
+) {
+    let changed = events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0));
+    let Some(loaded) = assets.get(&handle.0).filter(|_| changed) else {
+        return;
+    };
+    *catalog = loaded.clone();
+    eprintln!("Applied marathon.upgrades.ron");
+}
+
+fn reset_draft(mut commands: Commands) {
+    commands.insert_resource(Draft::default());
+}
+
+/// Offers a draft, pausing the run until it's picked from. Does nothing if every upgrade is
+/// used up. Draws from the run's [`GameRng::substream`] for `key`, so the same seed offers
+/// the same upgrades for the same key.
+pub(super) fn start_draft(
+    draft: &mut Draft,
+    catalog: &UpgradeCatalog,
+    rng: &GameRng,
+    key: u64,
+    next_pause: &mut NextState<Pause>,
+) {
+    if draft.offer(catalog, &mut rng.substream(key)) {
+        next_pause.set(Pause::Intermission);
+    }
+}
+
+fn choose(choice: DraftChoice) -> impl Fn(EventWriter<DraftChoice>) {
+    move |mut chosen| {
+        chosen.send(choice);
+    }
+}
+
+/// Tapping anywhere confirms too, but a tap meant for one of the upgrades shouldn't skip them.
+fn confirmed_without_touch(actions: Res<ButtonInput<Action>>, touch: TouchInput) -> bool {
+    actions.just_pressed(Action::Confirm) && !touch.any_held()
+}
+
+fn apply_draft_choice(
+    mut chosen: EventReader<DraftChoice>,
+    mut draft: ResMut<Draft>,
+    mut score: ResMut<Score>,
+    tuning: Res<GameTuning>,
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut SpeedLimits), With<Player>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    let Some(&choice) = chosen.read().last() else {
+        return;
+    };
+    if let DraftChoice::Pick(index) = choice {
+        let Some(upgrade) = draft.offered.get(index).cloned() else {
+            return;
+        };
+        for effect in &upgrade.effects {
+            effect.apply(&mut score, &mut draft.modifiers);
+        }
+        if let Ok((player, mut speed_limits)) = player_query.get_single_mut() {
+            *speed_limits = draft.modifiers.speed_limits(&tuning);
+            commands
+                .entity(player)
+                .insert(GravityScale(draft.modifiers.gravity));
+        }
+        eprintln!("Picked upgrade {}", upgrade.name);
+        draft.picked.push(upgrade.id);
+    }
+    draft.offered.clear();
+    next_pause.set(Pause::Running);
+}
@@ -0,0 +1,159 @@
+//! The random distributions level generation draws from, so spread can be tuned without
+//! touching the spawners.
+
+use bevy::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+use rand_distr::{Distribution, Normal, Triangular, Uniform};
+
+use super::PlatformKind;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GenerationConfig>()
+        .init_resource::<GameRng>();
+}
+
+/// The random number generator level generation draws from. It is reseeded at the start of
+/// every run, so runs with the same seed are laid out the same.
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct GameRng {
+    #[deref]
+    rng: StdRng,
+    /// What this run was seeded with: [`GameConfig::seed`](super::GameConfig::seed) if one was
+    /// set, otherwise a random one.
+    pub seed: u64,
+}
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+        }
+    }
+
+    /// A generator of its own for draws made at moments that depend on how the player plays,
+    /// like upgrade drafts. Drawing those from the layout's generator would shift everything
+    /// placed after them. The same seed and `key` always give the same generator.
+    pub fn substream(&self, key: u64) -> StdRng {
+        StdRng::seed_from_u64(self.seed ^ key.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+    }
+}
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct GenerationConfig {
+    /// Horizontal position of platforms, as a fraction of how far they can go to either side:
+    /// -1 is against the left edge, 1 against the right one. The result is then pulled in to
+    /// stay within jumping reach of the previous platform.
+    pub platform_x: Spread,
+    /// How far above its platform a hazard is placed.
+    pub hazard_height: Spread,
+    /// How far to either side of the center each end of an enemy's path lies.
+    pub enemy_reach: Spread,
+    /// Vertical offset of each end of an enemy's path from its hazard height.
+    pub enemy_height: Spread,
+    /// How likely each kind of platform is, relative to the others. The first platform of a
+    /// run is always [`PlatformKind::Solid`].
+    pub platform_kinds: Vec<PlatformWeight>,
+    /// Height (as counted by [`Score::height`](super::Score)) at which platform weights reach
+    /// their [`PlatformWeight::high`].
+    pub platform_weight_height: f32,
+    /// How far to either side of where it was placed a moving platform travels.
+    pub moving_platform_reach: Spread,
+    /// Half the width platforms are laid out across, centered on `x = 0`. It is fixed rather
+    /// than taken from the [`PlayArea`](super::PlayArea), so a seed lays out the same level
+    /// whatever the window size.
+    pub play_half_width: f32,
+}
+impl GenerationConfig {
+    /// Draws the kind of a platform placed at `height`. Falls back to
+    /// [`PlatformKind::Solid`] if no kind has any weight there.
+    pub fn platform_kind(&self, height: f32, rng: &mut impl Rng) -> PlatformKind {
+        let altitude = if self.platform_weight_height > 0.0 {
+            (height / self.platform_weight_height).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        self.platform_kinds
+            .choose_weighted(rng, |entry| entry.low.lerp(entry.high, altitude).max(0.0))
+            .map_or(PlatformKind::Solid, |entry| entry.kind)
+    }
+}
+
+/// An entry of [`GenerationConfig::platform_kinds`], with a weight that shifts from `low`
+/// to `high` as the run climbs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlatformWeight {
+    pub kind: PlatformKind,
+    pub low: f32,
+    pub high: f32,
+}
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        let weight = |kind, low, high| PlatformWeight { kind, low, high };
+        Self {
+            platform_x: Spread::Uniform {
+                min: -1.0,
+                max: 1.0,
+            },
+            hazard_height: Spread::Uniform {
+                min: 75.0,
+                max: 125.0,
+            },
+            enemy_reach: Spread::Normal {
+                mean: 325.0,
+                std_dev: 35.0,
+            },
+            enemy_height: Spread::Normal {
+                mean: 0.0,
+                std_dev: 20.0,
+            },
+            platform_kinds: vec![
+                weight(PlatformKind::Solid, 0.6, 0.3),
+                weight(PlatformKind::Moving, 0.15, 0.2),
+                weight(PlatformKind::Fragile, 0.0, 0.2),
+                weight(PlatformKind::Blinking, 0.05, 0.15),
+                weight(PlatformKind::Spring, 0.1, 0.05),
+                weight(PlatformKind::Ice, 0.05, 0.1),
+                weight(PlatformKind::Conveyor, 0.0, 0.1),
+            ],
+            platform_weight_height: 10_000.0,
+            moving_platform_reach: Spread::Uniform {
+                min: 40.0,
+                max: 100.0,
+            },
+            // the play area of a default 1280 pixel wide window
+            play_half_width: 640.0,
+        }
+    }
+}
+
+/// A distribution to draw a value from.
+///
+/// Invalid parameters (a negative deviation, a mode outside its range) give the center value
+/// instead of panicking mid-run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Spread {
+    Uniform { min: f32, max: f32 },
+    Normal { mean: f32, std_dev: f32 },
+    Triangular { min: f32, max: f32, mode: f32 },
+}
+impl Spread {
+    pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+        match *self {
+            Spread::Uniform { min, max } if min <= max => {
+                Uniform::new_inclusive(min, max).sample(rng)
+            }
+            Spread::Normal { mean, std_dev } => Normal::new(mean, std_dev)
+                .map(|normal| normal.sample(rng))
+                .unwrap_or(mean),
+            Spread::Triangular { min, max, mode } => Triangular::new(min, max, mode)
+                .map(|triangular| triangular.sample(rng))
+                .unwrap_or(mode),
+            Spread::Uniform { min, max } => (min + max) / 2.0,
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Spikes and enemies, and the player dying on touching them.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    aggro,
+    physics::{InterpolationMode, Interpolator, Line, LineInterpolatorBundle},
+    Collider, ColliderFromImage, DeathCause, DespawnBelowCamera, EntityPool, GameRng, GameSet,
+    GameTuning, GenerationConfig, Player, PlayerDied, PoolKind, RenderInterpolation,
+};
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<PlayerDamaged>()
+        .add_event::<HazardSpawned>()
+        .add_systems(
+            FixedUpdate,
+            (damage_player_on_contact, kill_player_on_damage)
+                .chain()
+                .in_set(GameSet::Damage),
+        );
+}
+
+/// Kills the player on touch. Which kind it is gets reported in [`PlayerDied`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DamageSource {
+    Spikes,
+    AngryCloud,
+}
+impl std::fmt::Display for DamageSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            DamageSource::Spikes => "Spikes",
+            DamageSource::AngryCloud => "Angry Cloud",
+        })
+    }
+}
+impl DamageSource {
+    /// Places an enemy patrolling around `spawn_height_source`, and returns the event
+    /// announcing it.
+    pub(super) fn spawn_enemy(
+        mut commands: Commands,
+        pool: &mut EntityPool,
+        asset_server: Res<AssetServer>,
+        generation: &GenerationConfig,
+        tuning: &GameTuning,
+        rng: &mut GameRng,
+        spawn_height_source: f32,
+    ) -> HazardSpawned {
+        let mut random_line_point = |x_fn: fn(f32) -> f32| {
+            Vec2::new(
+                x_fn(generation.enemy_reach.sample(&mut **rng)),
+                spawn_height_source + generation.enemy_height.sample(&mut **rng),
+            )
+        };
+        let line = Line(random_line_point(|x| -x), random_line_point(|x| x));
+        let position = line.0;
+        eprintln!("Placed enemy going between {} and {}", line.0, line.1);
+        let sprite_bundle = SpriteBundle {
+            transform: Transform {
+                translation: line.0.extend(0.0),
+                ..default()
+            },
+            texture: asset_server.load("images/angry_cloud.png"),
+            ..default()
+        };
+        let enemy = pool
+            .take(&mut commands, PoolKind::Enemy)
+            .insert((
+                DamageSource::AngryCloud,
+                // the cloud is round and doesn't fill its sprite
+                ColliderFromImage::circle().with_shrink(0.75),
+                sprite_bundle,
+                StateScoped(Screen::InGame),
+                DespawnBelowCamera::default(),
+                LineInterpolatorBundle {
+                    line,
+                    interpolator: Interpolator {
+                        timer: Timer::from_seconds(
+                            tuning.enemy_crossing_seconds,
+                            TimerMode::Repeating,
+                        ),
+                        mode: InterpolationMode::BackAndForth(Default::default()),
+                        ..default()
+                    },
+                },
+                aggro::Aggro::default(),
+                RenderInterpolation::default(),
+            ))
+            .id();
+        HazardSpawned {
+            hazard: enemy,
+            source: DamageSource::AngryCloud,
+            position,
+        }
+    }
+
+    /// Places spikes at `spawn_pos`, and returns the event announcing them.
+    pub(super) fn spawn_spikes(
+        mut commands: Commands,
+        pool: &mut EntityPool,
+        asset_server: Res<AssetServer>,
+        spawn_pos: Vec2,
+    ) -> HazardSpawned {
+        let sprite_bundle = SpriteBundle {
+            transform: Transform {
+                translation: spawn_pos.extend(0.0),
+                ..default()
+            },
+            texture: asset_server.load("images/spikes.png"),
+            ..default()
+        };
+        let spikes = pool
+            .take(&mut commands, PoolKind::Spikes)
+            .insert((
+                DamageSource::Spikes,
+                ColliderFromImage::aabb(),
+                sprite_bundle,
+                StateScoped(Screen::InGame),
+                DespawnBelowCamera::default(),
+            ))
+            .id();
+        eprintln!("Placed spikes at {}", spawn_pos);
+        HazardSpawned {
+            hazard: spikes,
+            source: DamageSource::Spikes,
+            position: spawn_pos,
+        }
+    }
+}
+
+/// Sent when a hazard is placed ahead of the player, so decorations, mods and achievements can
+/// react to it without touching the spawner.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HazardSpawned {
+    pub hazard: Entity,
+    pub source: DamageSource,
+    /// Where it was placed. Enemies start out at one end of their path.
+    pub position: Vec2,
+}
+
+/// Sent when the player touches a hazard. Every hazard kills, so [`PlayerDied`] follows in the
+/// same step.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerDamaged {
+    pub position: Vec2,
+    /// May be despawned by the time this is read.
+    pub hazard: Entity,
+    pub source: DamageSource,
+}
+
+fn damage_player_on_contact(
+    mut damaged: EventWriter<PlayerDamaged>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    damager_query: Query<(Entity, &Transform, &Collider, &DamageSource), Without<Player>>,
+) {
+    let Ok((player_transform, player_collider)) = player_query.get_single() else {
+        return;
+    };
+    let hazard = damager_query
+        .iter()
+        .find(|(_, damager_transform, damager_collider, _)| {
+            player_collider.overlaps(
+                player_transform.translation.truncate(),
+                ***damager_collider,
+                damager_transform.translation.truncate(),
+            )
+        });
+    if let Some((hazard, _, _, &source)) = hazard {
+        damaged.send(PlayerDamaged {
+            position: player_transform.translation.truncate(),
+            hazard,
+            source,
+        });
+    }
+}
+
+pub(super) fn kill_player_on_damage(
+    mut commands: Commands,
+    mut damaged: EventReader<PlayerDamaged>,
+    mut died: EventWriter<PlayerDied>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    let Some(damage) = damaged.read().last() else {
+        return;
+    };
+    let Ok(player_entity) = player_query.get_single() else {
+        return;
+    };
+    commands.entity(player_entity).despawn_recursive();
+    died.send(PlayerDied {
+        position: damage.position,
+        killer: Some(damage.hazard),
+        cause: DeathCause::Hazard(damage.source),
+    });
+    eprintln!("Killed player: {}.", damage.source)
+}
@@ -0,0 +1,71 @@
+//! Smooths out movement between fixed physics steps.
+//!
+//! Physics moves things in `FixedUpdate`, which doesn't line up with rendered frames, so drawing
+//! `Transform` as-is stutters. Entities with [`RenderInterpolation`] are drawn partway between
+//! their last two physics positions instead, and put back where physics left them before the
+//! next step.
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(FixedFirst, restore_physics_translation)
+        .add_systems(FixedLast, record_physics_translation)
+        .add_systems(
+            PostUpdate,
+            interpolate_translation.before(TransformSystem::TransformPropagate),
+        );
+}
+
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct RenderInterpolation {
+    previous: Vec3,
+    current: Vec3,
+    /// What was last drawn, to tell apart our own changes from something else moving the entity.
+    rendered: Vec3,
+}
+impl RenderInterpolation {
+    /// Moves every remembered position, for when the whole world moves at once.
+    pub fn shift(&mut self, offset: Vec3) {
+        self.previous += offset;
+        self.current += offset;
+        self.rendered += offset;
+    }
+
+    /// Whether nothing but physics and interpolation moved the entity since we last looked.
+    fn unmoved(&self, translation: Vec3) -> bool {
+        translation == self.current || translation == self.rendered
+    }
+}
+
+fn restore_physics_translation(mut query: Query<(&mut Transform, &mut RenderInterpolation)>) {
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        if interpolation.unmoved(transform.translation) {
+            transform.translation = interpolation.current;
+        } else {
+            // teleported (spawned, or reset for a new run), so jump there instead of sliding
+            interpolation.current = transform.translation;
+        }
+        interpolation.previous = interpolation.current;
+    }
+}
+
+fn record_physics_translation(mut query: Query<(&Transform, &mut RenderInterpolation)>) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.current = transform.translation;
+    }
+}
+
+fn interpolate_translation(
+    time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &mut RenderInterpolation)>,
+) {
+    let fraction = time.overstep_fraction();
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        if !interpolation.unmoved(transform.translation) {
+            // leave it to the next physics step to pick up the new position
+            continue;
+        }
+        transform.translation = interpolation.previous.lerp(interpolation.current, fraction);
+        interpolation.rendered = transform.translation;
+    }
+}
@@ -0,0 +1,79 @@
+//! Marathon mode: the climb is split into stages, with an upgrade [`Draft`](super::Draft)
+//! after each one.
+
+use bevy::prelude::*;
+
+use super::{
+    draft::{self, Draft, UpgradeCatalog},
+    GameConfig, GameMode, GameRng, GameSet, Score,
+};
+use crate::screen::{Pause, Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Marathon>()
+        .add_event::<StageCleared>()
+        .add_systems(OnEnter(Screen::InGame), reset_marathon)
+        .add_systems(
+            FixedUpdate,
+            clear_stages
+                .in_set(GameSet::Tracking)
+                .after(super::update_score)
+                .run_if(|config: Res<GameConfig>| config.mode == GameMode::Marathon),
+        );
+}
+
+/// Progress through a marathon run's stages.
+#[derive(Resource, Debug, Default)]
+pub struct Marathon {
+    /// Stages cleared so far.
+    pub stages_cleared: u32,
+    /// Seconds spent climbing the current stage, not counting pauses.
+    pub stage_seconds: f32,
+    /// How long the last cleared stage took.
+    pub last_stage_seconds: f32,
+}
+impl Marathon {
+    /// Height between stage clears, as counted by [`Score::height`].
+    pub const STAGE_HEIGHT: f32 = 2500.0;
+}
+
+/// Sent when a marathon run climbs past the end of a stage.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct StageCleared {
+    /// The stage that was cleared, counting from 1.
+    pub stage: u32,
+}
+
+fn reset_marathon(mut commands: Commands) {
+    commands.insert_resource(Marathon::default());
+}
+
+fn clear_stages(
+    time: Res<Time>,
+    score: Res<Score>,
+    catalog: Res<UpgradeCatalog>,
+    mut marathon: ResMut<Marathon>,
+    mut draft: ResMut<Draft>,
+    rng: Res<GameRng>,
+    mut stage_cleared: EventWriter<StageCleared>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    marathon.stage_seconds += time.delta_seconds();
+    let next_clear = (marathon.stages_cleared + 1) as f32 * Marathon::STAGE_HEIGHT;
+    if score.height < next_clear {
+        return;
+    }
+    marathon.stages_cleared += 1;
+    marathon.last_stage_seconds = std::mem::take(&mut marathon.stage_seconds);
+    stage_cleared.send(StageCleared {
+        stage: marathon.stages_cleared,
+    });
+    draft::start_draft(
+        &mut draft,
+        &catalog,
+        &rng,
+        marathon.stages_cleared.into(),
+        &mut next_pause,
+    );
+    eprintln!("Cleared stage {}", marathon.stages_cleared);
+}
@@ -1,274 +1,221 @@
-use bevy::math::NormedVectorSpace;
-use bevy::prelude::*;
-use rand::prelude::*;
-use rand_distr::*;
-use std::time::Duration;
+mod aggro;
+mod assist;
+mod blitz;
+mod camera;
+mod collision;
+mod controller;
+mod director;
+mod draft;
+mod generation;
+mod hazard;
+mod interpolation;
+mod marathon;
+mod objectives;
+mod origin;
+mod physics;
+mod platform;
+mod player;
+mod pool;
+mod reach;
+mod spawning;
+mod tuning;
 
-pub(super) fn plugin(game: &mut App) {
-    game.init_resource::<ScreenHeight>()
-        .init_resource::<LastPlatformSpawnHeight>()
-        .add_systems(Startup, Player::spawn)
-        .add_systems(
-            FixedUpdate,
-            (
-                (
-                    (player_horizontal_control, step_physics).chain(),
-                    step_interpolation,
-                ),
-                (keep_player_in_bounds, screen_tracking),
-                (
-                    (platform_spawner, player_falling_jumping).chain(),
-                    kill_player_on_damage,
-                ),
-            )
-                .chain(),
-        );
-}
-
-#[derive(Component, Debug, Deref, DerefMut)]
-pub struct Velocity(pub Vec2);
-impl Velocity {
-    pub const JUMP_VELOCITY: f32 = 575.0;
-    pub const GRAVITY: f32 = 225.0;
-    pub const MAX_FALL_SPEED: f32 = 700.0;
-    pub const HORIZONTAL_ACCELERATION: f32 = 550.0;
-    pub const MAX_HORIZONTAL_SPEED: f32 = 460.0;
-}
-
-#[derive(Bundle)]
-pub struct LineInterpolatorBundle {
-    line: Line,
-    interpolator: Interpolator,
-}
-
-#[derive(Component, Debug)]
-pub struct Line(pub Vec2, pub Vec2);
-
-#[derive(Component, Debug, Default)]
-pub struct Interpolator {
-    timer: Timer,
-    mode: InterpolationMode,
-}
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, utils::SystemTime};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default)]
-pub enum InterpolationMode {
-    #[default]
-    Wrapping,
-    BackAndForth(Direction),
-}
-
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
-pub enum Direction {
-    #[default]
-    Forward,
-    Backward,
-}
-impl std::ops::Not for Direction {
-    type Output = Direction;
-
-    fn not(self) -> Self::Output {
-        match self {
-            Direction::Forward => Direction::Backward,
-            Direction::Backward => Direction::Forward,
-        }
-    }
-}
+pub use aggro::EnemyAlerted;
+pub use blitz::{BlitzClock, TimeRanOut};
+pub use camera::{CameraFollow, GameCamera, PlayArea};
+pub use collision::{Collider, ColliderFromImage, Shape};
+pub use controller::{KinematicController, LastContact, Surface};
+pub use director::NearMiss;
+pub use draft::{
+    Draft, DraftChoice, Synergy, UpgradeCatalog, UpgradeDef, UpgradeEffect, UpgradeModifiers,
+};
+pub use generation::{GameRng, GenerationConfig, PlatformWeight, Spread};
+pub use hazard::{DamageSource, HazardSpawned, PlayerDamaged};
+pub use interpolation::RenderInterpolation;
+pub use marathon::{Marathon, StageCleared};
+pub use objectives::{Goal, Objective, ObjectiveConfig, ObjectiveOutcome, Objectives};
+pub use origin::{RebaseConfig, WorldOrigin};
+pub use physics::{GravityScale, SpeedLimits, Velocity};
+pub use platform::{
+    Blinking, Bounciness, Conveyor, Crumbling, PhasedOut, Platform, PlatformKind, PlatformSpawned,
+    Squash,
+};
+pub use player::{BoundsMode, PlatformBounced, Player, SurfaceModifier};
+pub use pool::{EntityPool, PoolKind};
+pub use spawning::{DespawnBelowCamera, LastPlatformSpawnHeight};
+pub use tuning::GameTuning;
 
-#[derive(Component, Debug, Deref)]
-pub struct CollisionBox(pub Box);
+use crate::{
+    input::Action,
+    screen::{Pause, Screen},
+};
 
-#[derive(Debug)]
-pub struct Box {
-    pub width: f32,
-    pub height: f32,
+/// Configuration the game was set up with, readable by systems as a resource.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct GameConfig {
+    /// Seed for level generation. `None` means a random seed every run, see [`GameRng`].
+    /// Daily challenges ignore it.
+    pub seed: Option<u64>,
+    /// The mode of the current or next run. The main menu changes it.
+    pub mode: GameMode,
 }
-impl From<Vec2> for Box {
-    fn from(value: Vec2) -> Self {
-        Self {
-            width: value.x,
-            height: value.y,
+impl GameConfig {
+    /// The seed the next run is generated from.
+    pub fn run_seed(&self) -> Option<u64> {
+        match self.mode {
+            GameMode::Endless | GameMode::Blitz | GameMode::Marathon => self.seed,
+            GameMode::Daily => Some(daily_seed()),
         }
     }
 }
-impl Box {
-    fn test_overlap(&self, self_pos: Vec2, other: &Self, other_pos: Vec2) -> bool {
-        let combined_width = self.width + other.width;
-        let combined_height = self.height + other.height;
-        let x_distance = self_pos.x.distance(other_pos.x);
-        let y_distance = self_pos.y.distance(other_pos.y);
-        x_distance <= combined_width || y_distance <= combined_height
-    }
-}
 
-fn player_horizontal_control(
-    time: Res<Time>,
-    mut player_query: Query<&mut Velocity, With<Player>>,
-    kb: Res<ButtonInput<KeyCode>>,
-) {
-    let Ok(mut player_velocity) = player_query.get_single_mut() else {
-        return;
-    };
-    let left_press = kb.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]);
-    let right_press = kb.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]);
-    match (left_press, right_press) {
-        (true, true) | (false, false) => (),
-        (true, false) => {
-            player_velocity.x = f32::max(
-                -Velocity::MAX_HORIZONTAL_SPEED,
-                player_velocity.x - (Velocity::HORIZONTAL_ACCELERATION * time.delta_seconds()),
-            )
-        }
-        (false, true) => {
-            player_velocity.x = f32::min(
-                Velocity::MAX_HORIZONTAL_SPEED,
-                player_velocity.x + (Velocity::HORIZONTAL_ACCELERATION * time.delta_seconds()),
-            )
-        }
-    }
-}
-
-fn step_physics(time: Res<Time>, mut physics_query: Query<(&mut Transform, &Velocity)>) {
-    for (mut transform, velocity) in physics_query.iter_mut() {
-        transform.translation += velocity.0.extend(0.0) * time.delta_seconds();
-    }
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    /// Climb for as long as you survive.
+    #[default]
+    Endless,
+    /// Endless, but everyone gets the same layout for the day.
+    Daily,
+    /// As high as you can get in [`BlitzClock::SECONDS`]. No objectives, the score is the
+    /// height alone.
+    Blitz,
+    /// Endless, split into stages of [`Marathon::STAGE_HEIGHT`] with an upgrade pick between
+    /// them.
+    Marathon,
 }
-fn step_interpolation(
-    time: Res<Time>,
-    mut interpolation_query: Query<(&mut Transform, &Line, &mut Interpolator)>,
-) {
-    for (mut transform, line, mut interpolator) in interpolation_query.iter_mut() {
-        interpolator.timer.tick(time.delta());
 
-        if interpolator.timer.finished() {
-            if let InterpolationMode::BackAndForth(dir) = &mut interpolator.mode {
-                *dir = !*dir;
-            }
-        }
-
-        let t = match interpolator.mode {
-            InterpolationMode::Wrapping | InterpolationMode::BackAndForth(Direction::Forward) => {
-                interpolator.timer.fraction()
-            }
-            InterpolationMode::BackAndForth(Direction::Backward) => {
-                1.0 - interpolator.timer.fraction()
-            }
-        };
-        transform.translation = line.0.lerp(line.1, t).extend(0.0);
-    }
+/// The daily challenge's seed: days since the Unix epoch, so it changes for everyone at
+/// midnight UTC.
+pub fn daily_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / 86_400)
 }
 
-fn keep_player_in_bounds(mut player_query: Query<(&mut Transform, &CollisionBox, &mut Velocity)>) {
-    let Ok((mut player_transform, player_collision_box, mut player_velocity)) =
-        player_query.get_single_mut()
-    else {
-        return;
-    };
-    let screen_width = 128.0; // arbitrary, not accurate to anything
-    let allowed_width = screen_width - player_collision_box.width;
-    if !(-allowed_width..=allowed_width).contains(&player_transform.translation.x) {
-        player_transform.translation.x = f32::clamp(
-            player_transform.translation.x,
-            -allowed_width,
-            allowed_width,
-        );
-        player_velocity.x = 0.0;
-    }
+/// The stages of a fixed step, in the order they run. They only run while the game isn't
+/// paused.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    /// Turning input into velocity.
+    Control,
+    /// Moving entities by their velocity, or along their path.
+    Movement,
+    /// Keeping the player in bounds and following how high they got.
+    Tracking,
+    /// Placing platforms and hazards ahead of the player.
+    Spawning,
+    /// Bounces and gravity, picked up by the next step's movement.
+    Forces,
+    /// Killing the player.
+    Damage,
+    /// Clearing away what the player left behind.
+    Cleanup,
 }
 
-fn player_falling_jumping(
-    time: Res<Time>,
-    mut player_query: Query<(&Transform, &CollisionBox, &mut Velocity), With<Player>>,
-    platform_query: Query<(&Transform, &CollisionBox), With<Platform>>,
-) {
-    let Ok((player_transform, player_collision_box, mut player_velocity)) =
-        player_query.get_single_mut()
-    else {
-        return;
-    };
-    // brute force testing is adequate for the small amount of platforms existing at once
-    if player_velocity.y <= 0.0
-        && platform_query
-            .iter()
-            .any(|(platform_transform, platform_collision_box)| {
-                player_collision_box.test_overlap(
-                    player_transform.translation.truncate(),
-                    platform_collision_box,
-                    platform_transform.translation.truncate(),
-                )
-            })
-    {
-        // jump
-        player_velocity.y = Velocity::JUMP_VELOCITY;
-    } else {
-        // falling via gravity
-        player_velocity.y = f32::max(
-            -Velocity::MAX_FALL_SPEED,
-            player_velocity.y - (Velocity::GRAVITY * time.delta_seconds()),
+pub(super) fn plugin(game: &mut App) {
+    game.configure_sets(
+        FixedUpdate,
+        (
+            GameSet::Control,
+            GameSet::Movement,
+            GameSet::Tracking,
+            GameSet::Spawning,
+            GameSet::Forces,
+            GameSet::Damage,
+            GameSet::Cleanup,
         )
-    }
+            .chain()
+            .run_if(in_state(Pause::Running)),
+    )
+    .init_resource::<ScreenHeight>()
+    .init_resource::<Score>()
+    .init_resource::<HeightMilestones>()
+    .init_resource::<LastRunEnd>()
+    .init_resource::<EntityPool>()
+    // read by most of the others
+    .add_plugins(tuning::plugin)
+    // the simulation itself
+    .add_plugins((
+        camera::plugin,
+        collision::plugin,
+        controller::plugin,
+        generation::plugin,
+        hazard::plugin,
+        interpolation::plugin,
+        origin::plugin,
+        physics::plugin,
+        platform::plugin,
+        player::plugin,
+        spawning::plugin,
+    ))
+    // what a run is played for, and how it adapts
+    .add_plugins((
+        aggro::plugin,
+        assist::plugin,
+        blitz::plugin,
+        director::plugin,
+        draft::plugin,
+        marathon::plugin,
+        objectives::plugin,
+    ))
+    .add_systems(OnEnter(Screen::InGame), reset_run)
+    .add_event::<HeightMilestoneReached>()
+    .add_event::<PlayerDied>()
+    .add_systems(
+        Update,
+        (
+            end_run_on_death.run_if(in_state(Screen::InGame)),
+            quit_run.run_if(in_state(Pause::Paused).and_then(input_just_pressed(Action::Quit))),
+        ),
+    )
+    .add_systems(
+        FixedUpdate,
+        (screen_tracking, update_score, announce_height_milestones)
+            .chain()
+            .in_set(GameSet::Tracking),
+    );
 }
-#[derive(Component, Debug)]
-pub struct Player;
-impl Player {
-    pub const SPAWN_VELOCITY: Velocity = Velocity(Vec2::new(0.0, 550.0));
 
-    fn spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
-        let sprite_bundle = SpriteBundle {
-            texture: asset_server.load("images/guy.png"),
-            ..default()
-        };
-        commands.spawn((
-            Player,
-            CollisionBox(Box::from(sprite_bundle.transform.scale.truncate())),
-            Self::SPAWN_VELOCITY,
-            sprite_bundle,
-        ));
-    }
-}
-
-#[derive(Component, Debug)]
-pub struct Platform;
-impl Platform {
-    pub const MIN_DISTANCE: f32 = 175.0;
-    fn spawn_single(
-        mut commands: Commands,
-        asset_server: Res<AssetServer>,
-        spawn_height: f32,
-    ) -> f32 {
-        let standard_deviation = 25.0;
-        let x = thread_rng().gen_range(-standard_deviation..=standard_deviation);
-        let sprite_bundle = SpriteBundle {
-            transform: Transform {
-                translation: Vec3::new(x, spawn_height, 0.0),
-                ..default()
-            },
-            texture: asset_server.load("images/box.png"),
-            ..default()
-        };
-        eprintln!("Placed platform at {}", Vec2 { x, y: spawn_height });
-        commands.spawn((
-            Platform,
-            CollisionBox(Box::from(sprite_bundle.transform.scale.truncate())),
-            sprite_bundle,
-        ));
-        x
+/// Puts everything a previous run changed back to how a fresh run starts.
+/// The run's entities are already gone, as they are scoped to [`Screen::InGame`].
+fn reset_run(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    follow: Res<CameraFollow>,
+    director_config: Res<director::DirectorConfig>,
+    tuning: Res<GameTuning>,
+    mut camera_query: Query<&mut Transform, With<GameCamera>>,
+) {
+    commands.insert_resource(
+        config
+            .run_seed()
+            .map_or_else(GameRng::default, GameRng::new),
+    );
+    commands.insert_resource(ScreenHeight::default());
+    commands.insert_resource(LastPlatformSpawnHeight::default());
+    commands.insert_resource(spawning::LastPlatformX::default());
+    commands.insert_resource(Score::default());
+    commands.insert_resource(HeightMilestones::default());
+    commands.insert_resource(LastRunEnd::default());
+    commands.insert_resource(WorldOrigin::default());
+    // anything left in the pool was despawned along with the last run
+    commands.insert_resource(EntityPool::default());
+    // the director adapted to the last run, and would carry that over otherwise
+    commands.insert_resource(director::PacingDirector::new(&director_config, &tuning));
+    for mut camera_transform in camera_query.iter_mut() {
+        camera_transform.translation.y = follow.offset;
     }
 }
 
 fn screen_tracking(
     player_transform: Query<&Transform, With<Player>>,
-    mut camera_transform: Query<&mut Transform, (With<Camera>, Without<Player>)>,
     mut screen_height: ResMut<ScreenHeight>,
 ) {
     if let Ok(player_transform) = player_transform.get_single() {
-        if player_transform.translation.y >= screen_height.0 {
-            screen_height.0 = player_transform.translation.y;
-            camera_transform
-                .get_single_mut()
-                .expect("camera exists")
-                .translation
-                .y = screen_height.0 + 250.0;
-        }
+        screen_height.0 = screen_height.0.max(player_transform.translation.y);
     }
 }
 
@@ -276,128 +223,102 @@ fn screen_tracking(
 #[derive(Resource, Debug, Default)]
 pub struct ScreenHeight(pub f32);
 
+/// The score of the current run.
 #[derive(Resource, Debug, Default)]
-pub struct LastPlatformSpawnHeight(pub f32);
+pub struct Score {
+    /// Best height reached this run.
+    pub height: f32,
+    /// Points awarded on top of the height.
+    pub bonus: u32,
+}
+impl Score {
+    pub fn total(&self) -> u32 {
+        self.height.max(0.0) as u32 + self.bonus
+    }
+}
 
-fn platform_spawner(
-    mut commands: Commands,
+fn update_score(
     screen_height: Res<ScreenHeight>,
-    asset_server: Res<AssetServer>,
-    mut last_platform_spawn_height: ResMut<LastPlatformSpawnHeight>,
-    mut non_initial: Local<bool>,
+    origin: Res<WorldOrigin>,
+    mut score: ResMut<Score>,
 ) {
-    const SPAWN_BOUNDS: f32 = 128.0;
-    while screen_height.0 + SPAWN_BOUNDS >= last_platform_spawn_height.0 + Platform::MIN_DISTANCE {
-        last_platform_spawn_height.0 = screen_height.0 + SPAWN_BOUNDS + Platform::MIN_DISTANCE;
-        let x = Platform::spawn_single(
-            commands.reborrow(),
-            Res::clone(&asset_server),
-            last_platform_spawn_height.0,
-        );
-        if *non_initial {
-            let offset = thread_rng().gen_range(75.0..=125.0);
-            if thread_rng().gen_ratio(1, 4) {
-                // 1/4 chance for platform to have a small spike somewhere on it
-                DamageSource::spawn_spikes(
-                    commands.reborrow(),
-                    Res::clone(&asset_server),
-                    Vec2::new(x, last_platform_spawn_height.0 + offset),
-                );
-            }
+    score.height = origin.height(screen_height.0);
+}
 
-            if thread_rng().gen_ratio(1, 7) {
-                // 1/7 chance to spawn an enemy above the platform somewhere
-                DamageSource::spawn_enemy(
-                    commands.reborrow(),
-                    Res::clone(&asset_server),
-                    last_platform_spawn_height.0 + offset,
-                );
-            }
-        } else {
-            *non_initial = true
-        }
-    }
+/// Sent each time the run's best height passes another multiple of
+/// [`HeightMilestoneReached::INTERVAL`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HeightMilestoneReached {
+    /// The height of the milestone, as counted by [`Score::height`].
+    pub height: f32,
+}
+impl HeightMilestoneReached {
+    pub const INTERVAL: f32 = 1000.0;
 }
 
-#[derive(Component, Debug)]
-pub struct DamageSource;
-impl DamageSource {
-    fn spawn_enemy(
-        mut commands: Commands,
-        asset_server: Res<AssetServer>,
-        spawn_height_source: f32,
-    ) {
-        let mut rng = thread_rng();
-        let half_x_distance = 325.0;
-        let x_distribution = Normal::new(0.0, 35.0).unwrap();
-        let y_distribution = Normal::new(0.0, 20.0).unwrap();
-        let mut random_line_point = |x_fn: fn(f32) -> f32| {
-            Vec2::new(
-                x_fn(half_x_distance + x_distribution.sample(&mut rng)),
-                spawn_height_source + y_distribution.sample(&mut rng),
-            )
-        };
-        let line = Line(random_line_point(|x| -x), random_line_point(|x| x));
-        eprintln!("Placed enemy going between {} and {}", line.0, line.1);
-        let sprite_bundle = SpriteBundle {
-            transform: Transform {
-                translation: line.0.extend(0.0),
-                ..default()
-            },
-            texture: asset_server.load("images/angry_cloud.png"),
-            ..default()
-        };
-        commands.spawn((
-            DamageSource,
-            CollisionBox(Box::from(sprite_bundle.transform.scale.truncate())),
-            sprite_bundle,
-            LineInterpolatorBundle {
-                line,
-                interpolator: Interpolator {
-                    timer: Timer::new(Duration::from_millis(1250), TimerMode::Repeating),
-                    mode: InterpolationMode::BackAndForth(Default::default()),
-                },
-            },
-        ));
-    }
-    fn spawn_spikes(mut commands: Commands, asset_server: Res<AssetServer>, spawn_pos: Vec2) {
-        let sprite_bundle = SpriteBundle {
-            transform: Transform {
-                translation: spawn_pos.extend(0.0),
-                ..default()
-            },
-            texture: asset_server.load("images/spikes.png"),
-            ..default()
-        };
-        commands.spawn((
-            DamageSource,
-            CollisionBox(Box::from(sprite_bundle.transform.scale.truncate())),
-            sprite_bundle,
-        ));
-        eprintln!("Placed spikes at {}", spawn_pos);
+/// How many height milestones the current run has passed.
+#[derive(Resource, Debug, Default)]
+struct HeightMilestones(u32);
+
+fn announce_height_milestones(
+    score: Res<Score>,
+    mut milestones: ResMut<HeightMilestones>,
+    mut reached: EventWriter<HeightMilestoneReached>,
+) {
+    let passed = (score.height / HeightMilestoneReached::INTERVAL).max(0.0) as u32;
+    while milestones.0 < passed {
+        milestones.0 += 1;
+        reached.send(HeightMilestoneReached {
+            height: milestones.0 as f32 * HeightMilestoneReached::INTERVAL,
+        });
     }
 }
 
-fn kill_player_on_damage(
-    mut commands: Commands,
-    player_query: Query<(Entity, &Transform, &CollisionBox), With<Player>>,
-    damager_query: Query<(&Transform, &CollisionBox), (With<DamageSource>, Without<Player>)>,
+/// Sent when the player dies, ending the run. Deaths to hazards follow a [`PlayerDamaged`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerDied {
+    pub position: Vec2,
+    /// The hazard that killed the player, if it was one. It may be despawned by the time this
+    /// is read.
+    pub killer: Option<Entity>,
+    pub cause: DeathCause,
+}
+
+/// What killed the player. Hazards that don't exist yet, like projectiles, lasers or lava,
+/// get their own [`DamageSource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeathCause {
+    Hazard(DamageSource),
+    /// Fell so far that there was nothing left to land on.
+    Fell,
+}
+
+/// Why a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunEnd {
+    Died(DeathCause),
+    /// Quit from the pause menu. Quit runs don't make the high score table.
+    Quit,
+    /// A [`GameMode::Blitz`] run's clock ran out.
+    TimeUp,
+}
+
+/// How the last run ended, for the game over screen.
+#[derive(Resource, Debug, Default)]
+pub struct LastRunEnd(pub Option<RunEnd>);
+
+fn end_run_on_death(
+    mut died: EventReader<PlayerDied>,
+    mut last_run_end: ResMut<LastRunEnd>,
+    mut next_screen: ResMut<NextState<Screen>>,
 ) {
-    let Ok((player_entity, player_transform, player_collision_box)) = player_query.get_single()
-    else {
-        return;
-    };
-    if damager_query
-        .iter()
-        .any(|(damager_transform, damager_collision_box)| {
-            player_collision_box.test_overlap(
-                player_transform.translation.truncate(),
-                damager_collision_box,
-                damager_transform.translation.truncate(),
-            )
-        })
-    {
-        commands.entity(player_entity).despawn();
-        eprintln!("Killed player.")
+    if let Some(death) = died.read().last() {
+        last_run_end.0 = Some(RunEnd::Died(death.cause));
+        next_screen.set(Screen::GameOver);
     }
 }
+
+fn quit_run(mut last_run_end: ResMut<LastRunEnd>, mut next_screen: ResMut<NextState<Screen>>) {
+    last_run_end.0 = Some(RunEnd::Quit);
+    next_screen.set(Screen::GameOver);
+}
@@ -0,0 +1,246 @@
+//! Optional short goals offered during a run, like "Climb 600 in 20s", that pay out bonus score.
+//!
+//! One is offered at a time, a while after the last one ended. Goals get bigger and pay more
+//! as the pacing director makes the run harder.
+
+use std::fmt;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use super::{
+    director::{DirectorConfig, PacingDirector},
+    GameConfig, GameMode, GameRng, PlatformBounced, Score,
+};
+use crate::{
+    input::Steering,
+    screen::{Pause, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ObjectiveConfig>()
+        .init_resource::<Objectives>()
+        .add_systems(OnEnter(Screen::InGame), reset_objectives)
+        .add_systems(
+            Update,
+            (offer_objective, track_objective).chain().run_if(
+                in_state(Pause::Running)
+                    .and_then(|config: Res<ObjectiveConfig>| config.enabled)
+                    // blitz is scored on height alone
+                    .and_then(|game: Res<GameConfig>| game.mode != GameMode::Blitz),
+            ),
+        );
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct ObjectiveConfig {
+    pub enabled: bool,
+    /// Seconds between one objective ending and the next being offered.
+    pub interval_seconds: f32,
+    /// Objectives to pick from, at their easiest.
+    pub templates: Vec<ObjectiveTemplate>,
+}
+impl Default for ObjectiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_seconds: 15.0,
+            templates: vec![
+                ObjectiveTemplate {
+                    goal: Goal::Climb { height: 600.0 },
+                    seconds: 20.0,
+                    reward: 50,
+                },
+                ObjectiveTemplate {
+                    goal: Goal::Bounce { count: 8 },
+                    seconds: 15.0,
+                    reward: 30,
+                },
+                ObjectiveTemplate {
+                    goal: Goal::ClimbWithoutSteering { height: 300.0 },
+                    seconds: 20.0,
+                    reward: 75,
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectiveTemplate {
+    pub goal: Goal,
+    /// Time allowed to finish it.
+    pub seconds: f32,
+    pub reward: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Goal {
+    /// Raise the best height by this much.
+    Climb {
+        height: f32,
+    },
+    Bounce {
+        count: u32,
+    },
+    /// Raise the best height by this much without touching the controls. Steering fails it.
+    ClimbWithoutSteering {
+        height: f32,
+    },
+}
+impl Goal {
+    /// The same goal, that much bigger.
+    fn scaled(self, factor: f32) -> Self {
+        match self {
+            Goal::Climb { height } => Goal::Climb {
+                height: height * factor,
+            },
+            Goal::Bounce { count } => Goal::Bounce {
+                count: (count as f32 * factor).round() as u32,
+            },
+            Goal::ClimbWithoutSteering { height } => Goal::ClimbWithoutSteering {
+                height: height * factor,
+            },
+        }
+    }
+
+    fn target(self) -> f32 {
+        match self {
+            Goal::Climb { height } | Goal::ClimbWithoutSteering { height } => height,
+            Goal::Bounce { count } => count as f32,
+        }
+    }
+}
+impl fmt::Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Goal::Climb { height } => write!(f, "Climb {height:.0}"),
+            Goal::Bounce { count } => write!(f, "Bounce {count} times"),
+            Goal::ClimbWithoutSteering { height } => {
+                write!(f, "Climb {height:.0} without steering")
+            }
+        }
+    }
+}
+
+/// An objective being worked on.
+#[derive(Debug, Clone)]
+pub struct Objective {
+    pub goal: Goal,
+    pub reward: u32,
+    pub seconds_left: f32,
+    /// How much of the goal's target is done.
+    pub progress: f32,
+    /// The best height when it was offered, for climbing goals.
+    start_height: f32,
+}
+impl Objective {
+    pub fn target(&self) -> f32 {
+        self.goal.target()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectiveOutcome {
+    pub goal: Goal,
+    /// The reward paid out, or `None` if it was failed.
+    pub reward: Option<u32>,
+}
+
+/// The run's objectives, for the HUD to show.
+#[derive(Resource, Debug, Default)]
+pub struct Objectives {
+    pub active: Option<Objective>,
+    /// How the last one went, until the next one is offered.
+    pub last_outcome: Option<ObjectiveOutcome>,
+    seconds_until_next: f32,
+    /// How many have been offered this run, keying each one's [`GameRng::substream`].
+    offered: u64,
+}
+
+fn reset_objectives(mut commands: Commands, config: Res<ObjectiveConfig>) {
+    commands.insert_resource(Objectives {
+        seconds_until_next: config.interval_seconds,
+        ..default()
+    });
+}
+
+/// Sets objective substream keys apart from the draft's, which count cleared stages.
+const SUBSTREAM_KEYS: u64 = 1 << 32;
+
+fn offer_objective(
+    time: Res<Time>,
+    rng: Res<GameRng>,
+    config: Res<ObjectiveConfig>,
+    director_config: Res<DirectorConfig>,
+    director: Res<PacingDirector>,
+    score: Res<Score>,
+    mut objectives: ResMut<Objectives>,
+) {
+    if objectives.active.is_some() {
+        return;
+    }
+    objectives.seconds_until_next -= time.delta_seconds();
+    if objectives.seconds_until_next > 0.0 {
+        return;
+    }
+    // the same seed offers the same objectives, however the run has gone between them
+    let mut objective_rng = rng.substream(SUBSTREAM_KEYS + objectives.offered);
+    let Some(template) = config.templates.choose(&mut objective_rng) else {
+        return;
+    };
+    objectives.offered += 1;
+    // up to twice the goal for twice the reward, at the hardest
+    let factor = 1.0 + director.difficulty(&director_config);
+    objectives.active = Some(Objective {
+        goal: template.goal.scaled(factor),
+        reward: (template.reward as f32 * factor).round() as u32,
+        seconds_left: template.seconds,
+        progress: 0.0,
+        start_height: score.height,
+    });
+    objectives.last_outcome = None;
+}
+
+fn track_objective(
+    time: Res<Time>,
+    config: Res<ObjectiveConfig>,
+    steering: Res<Steering>,
+    mut bounced: EventReader<PlatformBounced>,
+    mut score: ResMut<Score>,
+    mut objectives: ResMut<Objectives>,
+) {
+    let bounces = bounced.read().count();
+    let Some(objective) = objectives.active.as_mut() else {
+        return;
+    };
+    objective.seconds_left -= time.delta_seconds();
+    let failed = match objective.goal {
+        Goal::Climb { .. } => {
+            objective.progress = score.height - objective.start_height;
+            false
+        }
+        Goal::Bounce { .. } => {
+            objective.progress += bounces as f32;
+            false
+        }
+        Goal::ClimbWithoutSteering { .. } => {
+            objective.progress = score.height - objective.start_height;
+            steering.0 != 0.0
+        }
+    };
+    let completed = !failed && objective.progress >= objective.target();
+    if !completed && !failed && objective.seconds_left > 0.0 {
+        return;
+    }
+    let reward = completed.then_some(objective.reward);
+    if let Some(reward) = reward {
+        score.bonus += reward;
+    }
+    objectives.last_outcome = Some(ObjectiveOutcome {
+        goal: objective.goal,
+        reward,
+    });
+    objectives.active = None;
+    objectives.seconds_until_next = config.interval_seconds;
+}
@@ -0,0 +1,91 @@
+//! Keeps coordinates small during long runs by moving the world back down now and then.
+//!
+//! `f32` positions get coarser the further they are from zero, which would eventually show up
+//! as jittery movement and collisions. Shifting everything by the same amount is invisible,
+//! and [`WorldOrigin`] remembers how far things were moved so heights shown to the player
+//! keep counting up.
+
+use bevy::prelude::*;
+
+use super::{
+    director::PacingDirector, physics::Line, GameCamera, GameSet, LastContact, RenderInterpolation,
+    ScreenHeight,
+};
+use crate::screen::Pause;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<WorldOrigin>()
+        .init_resource::<RebaseConfig>()
+        .add_systems(
+            FixedUpdate,
+            rebase_world
+                .after(GameSet::Tracking)
+                .before(GameSet::Spawning)
+                .run_if(in_state(Pause::Running)),
+        );
+}
+
+/// How far the world has been moved down this run.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct WorldOrigin(pub f32);
+impl WorldOrigin {
+    /// The height in the run's terms of a height in world coordinates.
+    pub fn height(&self, world_height: f32) -> f32 {
+        self.0 + world_height
+    }
+
+    /// The height in world coordinates of a height in the run's terms.
+    pub fn world_height(&self, height: f64) -> f32 {
+        (height - f64::from(self.0)) as f32
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct RebaseConfig {
+    /// Once the screen climbs this high, the world is moved down by as much.
+    pub height: f32,
+}
+impl Default for RebaseConfig {
+    fn default() -> Self {
+        Self { height: 10_000.0 }
+    }
+}
+
+fn rebase_world(
+    config: Res<RebaseConfig>,
+    mut origin: ResMut<WorldOrigin>,
+    mut screen_height: ResMut<ScreenHeight>,
+    mut director: ResMut<PacingDirector>,
+    // everything drawn in the world, children move along with their parents
+    mut transform_query: Query<
+        &mut Transform,
+        (Or<(With<Sprite>, With<GameCamera>)>, Without<Parent>),
+    >,
+    mut line_query: Query<&mut Line>,
+    mut interpolation_query: Query<&mut RenderInterpolation>,
+    mut contact_query: Query<&mut LastContact>,
+) {
+    // a height of zero or less would rebase every step
+    if config.height <= 0.0 || screen_height.0 < config.height {
+        return;
+    }
+    let shift = config.height;
+    let offset = Vec2::new(0.0, -shift);
+    origin.0 += shift;
+    screen_height.0 -= shift;
+    director.rebase(shift);
+    for mut transform in transform_query.iter_mut() {
+        transform.translation += offset.extend(0.0);
+    }
+    for mut line in line_query.iter_mut() {
+        line.0 += offset;
+        line.1 += offset;
+    }
+    for mut interpolation in interpolation_query.iter_mut() {
+        interpolation.shift(offset.extend(0.0));
+    }
+    for mut contact in contact_query.iter_mut() {
+        contact.position += offset;
+    }
+    eprintln!("Rebased the world, origin now at height {}", origin.0);
+}
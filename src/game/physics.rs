@@ -0,0 +1,159 @@
+//! Velocities, gravity and the fixed paths some entities move along instead.
+
+use bevy::prelude::*;
+
+use super::{GameSet, GameTuning, KinematicController};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        (
+            step_interpolation.in_set(GameSet::Movement),
+            apply_gravity.in_set(GameSet::Forces),
+        ),
+    );
+}
+
+#[derive(Component, Debug, Deref, DerefMut)]
+pub struct Velocity(pub Vec2);
+
+/// Caps on how fast an entity can move, applied in `step_physics`. Entities without it aren't
+/// capped at all. The player's come from [`GameTuning::player_speed_limits`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct SpeedLimits {
+    /// Fastest sideways speed, in either direction.
+    pub horizontal: f32,
+    /// Fastest speed in the direction gravity pulls. Moving against it isn't capped, so jumps
+    /// and springs keep their height.
+    pub fall: f32,
+}
+impl SpeedLimits {
+    /// Caps `velocity` for an entity with a [`GravityScale`] of `gravity_scale`, which decides
+    /// which way it falls.
+    pub fn clamp(self, velocity: Vec2, gravity_scale: f32) -> Vec2 {
+        let y = if gravity_scale < 0.0 {
+            velocity.y.min(self.fall)
+        } else {
+            velocity.y.max(-self.fall)
+        };
+        Vec2::new(velocity.x.clamp(-self.horizontal, self.horizontal), y)
+    }
+}
+
+/// Multiplies [`GameTuning::gravity`] for one entity: below 1 floats, above 1 drops like a stone,
+/// and negative rises like a balloon. Entities without it fall at the normal rate.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Deref, DerefMut)]
+pub struct GravityScale(pub f32);
+impl Default for GravityScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+#[derive(Bundle)]
+pub struct LineInterpolatorBundle {
+    pub(super) line: Line,
+    pub(super) interpolator: Interpolator,
+}
+
+#[derive(Component, Debug)]
+pub struct Line(pub Vec2, pub Vec2);
+
+#[derive(Component, Debug)]
+pub struct Interpolator {
+    pub(super) timer: Timer,
+    pub(super) mode: InterpolationMode,
+    /// How fast the timer runs compared to real time.
+    pub(super) speed: f32,
+}
+impl Interpolator {
+    /// How fast the entity is moving along `line`.
+    pub fn velocity(&self, line: &Line) -> Vec2 {
+        let seconds = self.timer.duration().as_secs_f32();
+        if seconds <= 0.0 {
+            return Vec2::ZERO;
+        }
+        let velocity = (line.1 - line.0) * self.speed / seconds;
+        match self.mode {
+            InterpolationMode::BackAndForth(Direction::Backward) => -velocity,
+            _ => velocity,
+        }
+    }
+}
+impl Default for Interpolator {
+    fn default() -> Self {
+        Self {
+            timer: default(),
+            mode: default(),
+            speed: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub enum InterpolationMode {
+    #[default]
+    Wrapping,
+    BackAndForth(Direction),
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Forward,
+    Backward,
+}
+impl std::ops::Not for Direction {
+    type Output = Direction;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Direction::Forward => Direction::Backward,
+            Direction::Backward => Direction::Forward,
+        }
+    }
+}
+
+fn step_interpolation(
+    time: Res<Time>,
+    mut interpolation_query: Query<(&mut Transform, &Line, &mut Interpolator)>,
+) {
+    for (mut transform, line, mut interpolator) in interpolation_query.iter_mut() {
+        let delta = time.delta().mul_f32(interpolator.speed);
+        interpolator.timer.tick(delta);
+
+        if interpolator.timer.finished() {
+            if let InterpolationMode::BackAndForth(dir) = &mut interpolator.mode {
+                *dir = !*dir;
+            }
+        }
+
+        let t = match interpolator.mode {
+            InterpolationMode::Wrapping | InterpolationMode::BackAndForth(Direction::Forward) => {
+                interpolator.timer.fraction()
+            }
+            InterpolationMode::BackAndForth(Direction::Backward) => {
+                1.0 - interpolator.timer.fraction()
+            }
+        };
+        transform.translation = line.0.lerp(line.1, t).extend(0.0);
+    }
+}
+
+fn apply_gravity(
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+    mut falling_query: Query<(
+        &mut Velocity,
+        Option<&GravityScale>,
+        Option<&KinematicController>,
+    )>,
+) {
+    for (mut velocity, gravity_scale, controller) in falling_query.iter_mut() {
+        if controller.is_some_and(|controller| controller.grounded) {
+            continue;
+        }
+        let scale = gravity_scale.map_or(1.0, |scale| scale.0);
+        velocity.y -= tuning.gravity * scale * time.delta_seconds();
+    }
+}
@@ -0,0 +1,369 @@
+//! The platforms the player bounces on.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{
+    controller,
+    physics::{InterpolationMode, Interpolator, Line, LineInterpolatorBundle},
+    reach, ColliderFromImage, DespawnBelowCamera, EntityPool, GameRng, GameSet, GameTuning,
+    GenerationConfig, PlatformBounced, PoolKind, RenderInterpolation, Surface,
+};
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<PlatformSpawned>()
+        .init_resource::<ConveyorFrames>()
+        .add_systems(Update, animate_conveyors)
+        .add_systems(
+            FixedUpdate,
+            (
+                blink_platforms
+                    .in_set(GameSet::Movement)
+                    .before(controller::step_physics),
+                (
+                    (crack_fragile_platforms, crumble_platforms).chain(),
+                    (squash_springs, unsquash_springs).chain(),
+                )
+                    .in_set(GameSet::Cleanup),
+            ),
+        );
+}
+
+#[derive(Component, Debug)]
+pub struct Platform;
+
+/// What a platform does, picked for each one from
+/// [`GenerationConfig::platform_kinds`].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlatformKind {
+    #[default]
+    Solid,
+    /// Travels side to side around where it was placed.
+    Moving,
+    /// Cracks when bounced off, and crumbles away shortly after.
+    Fragile,
+    /// Phases in and out on a timer, see [`Blinking`].
+    Blinking,
+    /// Launches the player much higher, see [`Bounciness`].
+    Spring,
+    /// Slippery, see [`Surface::Ice`].
+    Ice,
+    /// Pushes the player sideways on every bounce, see [`Conveyor`].
+    Conveyor,
+}
+
+/// What bouncing off a platform multiplies [`GameTuning::jump_velocity`] by. Platforms without
+/// one bounce the player at the normal speed.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Bounciness(pub f32);
+
+/// Sideways speed a conveyor platform adds to the player on every bounce. Its stripes scroll
+/// the same way.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Conveyor(pub f32);
+
+/// The frames of the conveyor's scrolling stripes, which all have the same size so the collider
+/// fits whichever one it was made from.
+#[derive(Resource, Debug)]
+struct ConveyorFrames(Vec<Handle<Image>>);
+impl FromWorld for ConveyorFrames {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self(
+            (1..=4)
+                .map(|frame| asset_server.load(format!("images/conveyor{frame}.png")))
+                .collect(),
+        )
+    }
+}
+impl ConveyorFrames {
+    const PER_SECOND: f32 = 12.0;
+}
+
+/// A spring platform squashing down after being bounced off, and springing back.
+#[derive(Component, Debug)]
+pub struct Squash(pub Timer);
+impl Squash {
+    pub const SECONDS: f32 = 0.25;
+    /// How much of its height the platform loses at the deepest point.
+    const DEPTH: f32 = 0.4;
+}
+impl Default for Squash {
+    fn default() -> Self {
+        Self(Timer::from_seconds(Self::SECONDS, TimerMode::Once))
+    }
+}
+
+/// Cycles a [`PlatformKind::Blinking`] platform between being there for
+/// [`GameTuning::platform_solid_seconds`] and gone for [`GameTuning::platform_gone_seconds`].
+#[derive(Component, Debug)]
+pub struct Blinking(pub Timer);
+impl Blinking {
+    /// How long before phasing out or in the platform starts fading.
+    pub const WARNING_SECONDS: f32 = 0.6;
+}
+
+/// Marks a [`Blinking`] platform while it is gone, so nothing lands on it.
+#[derive(Component, Debug)]
+pub struct PhasedOut;
+
+/// A fragile platform that has been bounced off, counting down until it is gone.
+#[derive(Component, Debug)]
+pub struct Crumbling(pub Timer);
+impl Crumbling {
+    pub const SECONDS: f32 = 0.4;
+}
+impl Default for Crumbling {
+    fn default() -> Self {
+        Self(Timer::from_seconds(Self::SECONDS, TimerMode::Once))
+    }
+}
+impl Platform {
+    /// Half the width of every platform image. Generation uses this instead of the loaded
+    /// image, so where platforms go doesn't depend on when their images finish loading.
+    pub const HALF_WIDTH: f32 = 64.0;
+
+    /// Places a platform at `spawn_height`, within reach of the `previous` one, and returns
+    /// the event announcing where it ended up. Platforms of most kinds are tinted to stand out.
+    pub(super) fn spawn_single(
+        mut commands: Commands,
+        pool: &mut EntityPool,
+        asset_server: Res<AssetServer>,
+        generation: &GenerationConfig,
+        tuning: &GameTuning,
+        rng: &mut GameRng,
+        previous: Vec2,
+        spawn_height: f32,
+        kind: PlatformKind,
+    ) -> PlatformSpawned {
+        // keeps the whole platform within the play area
+        let max_x = (generation.play_half_width - Self::HALF_WIDTH).max(0.0);
+        let reach = reach::max_horizontal_reach(tuning, spawn_height - previous.y)
+            .map_or(0.0, |reach| reach * tuning.reach_margin);
+        let x = (generation.platform_x.sample(&mut **rng) * max_x)
+            .clamp(previous.x - reach, previous.x + reach)
+            .clamp(-max_x, max_x);
+        let conveyor = (kind == PlatformKind::Conveyor).then(|| {
+            let direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+            Conveyor(direction * tuning.conveyor_speed)
+        });
+        let sprite_bundle = SpriteBundle {
+            transform: Transform {
+                translation: Vec3::new(x, spawn_height, 0.0),
+                ..default()
+            },
+            sprite: Sprite {
+                color: kind.color(),
+                flip_x: conveyor.is_some_and(|conveyor| conveyor.0 < 0.0),
+                ..default()
+            },
+            texture: asset_server.load(kind.image()),
+            ..default()
+        };
+        eprintln!("Placed platform at {}", Vec2 { x, y: spawn_height });
+        let mut platform = pool.take(&mut commands, PoolKind::Platform);
+        platform.insert((
+            Platform,
+            kind,
+            ColliderFromImage::aabb(),
+            sprite_bundle,
+            StateScoped(Screen::InGame),
+            DespawnBelowCamera::default(),
+        ));
+        if kind == PlatformKind::Moving {
+            let travel = generation.moving_platform_reach.sample(&mut **rng).max(0.0);
+            let (start, end) = ((x - travel).max(-max_x), (x + travel).min(max_x));
+            let mut timer =
+                Timer::from_seconds(tuning.platform_crossing_seconds, TimerMode::Repeating);
+            // starts out where it was placed instead of jumping to one end
+            if end > start {
+                timer.set_elapsed(timer.duration().mul_f32((x - start) / (end - start)));
+            }
+            platform.insert((
+                LineInterpolatorBundle {
+                    line: Line(Vec2::new(start, spawn_height), Vec2::new(end, spawn_height)),
+                    interpolator: Interpolator {
+                        timer,
+                        mode: InterpolationMode::BackAndForth(default()),
+                        ..default()
+                    },
+                },
+                RenderInterpolation::default(),
+            ));
+        }
+        match kind {
+            PlatformKind::Spring => {
+                platform.insert((Surface::Spring, Bounciness(tuning.spring_jump_multiplier)));
+            }
+            PlatformKind::Ice => {
+                platform.insert(Surface::Ice);
+            }
+            _ => {}
+        }
+        if let Some(conveyor) = conveyor {
+            platform.insert(conveyor);
+        }
+        if kind == PlatformKind::Blinking {
+            let cycle = tuning.platform_solid_seconds + tuning.platform_gone_seconds;
+            let mut timer = Timer::from_seconds(cycle.max(f32::EPSILON), TimerMode::Repeating);
+            // nearby blinking platforms shouldn't all phase out at once
+            timer.set_elapsed(timer.duration().mul_f32(rng.gen_range(0.0..1.0)));
+            platform.insert(Blinking(timer));
+        }
+        let platform = platform.id();
+        PlatformSpawned {
+            platform,
+            kind,
+            position: Vec2::new(x, spawn_height),
+        }
+    }
+}
+
+/// Sent when a platform is placed ahead of the player, so decorations, mods and achievements can
+/// react to it without touching the spawner.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlatformSpawned {
+    pub platform: Entity,
+    pub kind: PlatformKind,
+    pub position: Vec2,
+}
+
+impl PlatformKind {
+    pub(super) fn image(self) -> &'static str {
+        match self {
+            PlatformKind::Spring => "images/spring.png",
+            PlatformKind::Conveyor => "images/conveyor1.png",
+            _ => "images/box.png",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            PlatformKind::Solid | PlatformKind::Moving | PlatformKind::Conveyor => Color::WHITE,
+            PlatformKind::Fragile => Color::srgb(0.85, 0.65, 0.45),
+            PlatformKind::Blinking => Color::srgb(0.75, 0.6, 1.0),
+            PlatformKind::Spring => Color::srgb(0.6, 1.0, 0.6),
+            PlatformKind::Ice => Color::srgb(0.75, 0.95, 1.0),
+        }
+    }
+}
+
+/// Phases blinking platforms in and out, fading them out ahead of time so a jump can be timed.
+/// While gone they are left faintly visible, growing clearer before they come back.
+fn blink_platforms(
+    mut commands: Commands,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+    mut blinking_query: Query<(Entity, &mut Blinking, &mut Sprite, Has<PhasedOut>)>,
+) {
+    const GONE_ALPHA: f32 = 0.15;
+    const WARNED_ALPHA: f32 = 0.4;
+    for (entity, mut blinking, mut sprite, phased_out) in blinking_query.iter_mut() {
+        let elapsed = blinking.0.tick(time.delta()).elapsed_secs();
+        let solid = elapsed < tuning.platform_solid_seconds;
+        let phase_left = if solid {
+            tuning.platform_solid_seconds - elapsed
+        } else {
+            blinking.0.duration().as_secs_f32() - elapsed
+        };
+        let warning = (1.0 - phase_left / Blinking::WARNING_SECONDS).clamp(0.0, 1.0);
+        let alpha = if solid {
+            1.0 - warning * (1.0 - WARNED_ALPHA)
+        } else {
+            GONE_ALPHA + warning * (WARNED_ALPHA - GONE_ALPHA)
+        };
+        sprite.color = PlatformKind::Blinking.color().with_alpha(alpha);
+        if solid && phased_out {
+            commands.entity(entity).remove::<PhasedOut>();
+        } else if !solid && !phased_out {
+            commands.entity(entity).insert(PhasedOut);
+        }
+    }
+}
+
+fn crack_fragile_platforms(
+    mut commands: Commands,
+    mut bounced: EventReader<PlatformBounced>,
+    platform_query: Query<&PlatformKind, Without<Crumbling>>,
+) {
+    for bounce in bounced.read() {
+        if platform_query.get(bounce.platform) == Ok(&PlatformKind::Fragile) {
+            commands
+                .entity(bounce.platform)
+                .insert(Crumbling::default());
+        }
+    }
+}
+
+/// Flickers and fades cracked platforms, then sends them back to the pool.
+fn crumble_platforms(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pool: ResMut<EntityPool>,
+    mut crumbling_query: Query<(Entity, &mut Crumbling, &mut Sprite)>,
+) {
+    for (entity, mut crumbling, mut sprite) in crumbling_query.iter_mut() {
+        if crumbling.0.tick(time.delta()).finished() {
+            pool.recycle(&mut commands, entity, PoolKind::Platform);
+            continue;
+        }
+        let left = 1.0 - crumbling.0.fraction();
+        // alternates between darker and lighter every few steps, like it's cracking apart
+        let crack = if (crumbling.0.elapsed_secs() * 10.0).fract() < 0.5 {
+            0.6
+        } else {
+            1.0
+        };
+        let base = PlatformKind::Fragile.color().to_srgba();
+        sprite.color = Color::srgba(
+            base.red * crack,
+            base.green * crack,
+            base.blue * crack,
+            left,
+        );
+    }
+}
+
+fn squash_springs(
+    mut commands: Commands,
+    mut bounced: EventReader<PlatformBounced>,
+    spring_query: Query<(), With<Bounciness>>,
+) {
+    for bounce in bounced.read() {
+        if spring_query.contains(bounce.platform) {
+            commands.entity(bounce.platform).insert(Squash::default());
+        }
+    }
+}
+
+/// Squashes springs flat and wide, then lets them back out to their full size.
+fn unsquash_springs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut squash_query: Query<(Entity, &mut Squash, &mut Transform)>,
+) {
+    for (entity, mut squash, mut transform) in squash_query.iter_mut() {
+        if squash.0.tick(time.delta()).finished() {
+            transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<Squash>();
+            continue;
+        }
+        let amount = Squash::DEPTH * (squash.0.fraction() * std::f32::consts::PI).sin();
+        transform.scale = Vec3::new(1.0 + amount / 2.0, 1.0 - amount, 1.0);
+    }
+}
+
+/// Scrolls every conveyor's stripes in step, flipped for the ones going left.
+fn animate_conveyors(
+    time: Res<Time>,
+    frames: Res<ConveyorFrames>,
+    mut conveyor_query: Query<&mut Handle<Image>, With<Conveyor>>,
+) {
+    let frame = (time.elapsed_seconds() * ConveyorFrames::PER_SECOND) as usize % frames.0.len();
+    for mut image in conveyor_query.iter_mut() {
+        if *image != frames.0[frame] {
+            *image = frames.0[frame].clone();
+        }
+    }
+}
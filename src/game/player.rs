@@ -0,0 +1,280 @@
+//! The player: steering, bouncing, staying in the play area, and falling to their death.
+
+use bevy::prelude::*;
+
+use super::{
+    hazard,
+    physics::{Interpolator, Line, Velocity},
+    Bounciness, Collider, ColliderFromImage, Conveyor, DeathCause, GameSet, GameTuning,
+    KinematicController, LastContact, Platform, PlayArea, PlayerDied, RenderInterpolation,
+    ScreenHeight, Surface,
+};
+use crate::{input::Steering, screen::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<BoundsMode>()
+        .add_event::<PlatformBounced>()
+        .add_systems(OnEnter(Screen::InGame), Player::spawn)
+        .add_systems(Update, update_wrap_ghost.run_if(in_state(Screen::InGame)))
+        .add_systems(
+            FixedUpdate,
+            (
+                player_horizontal_control.in_set(GameSet::Control),
+                keep_player_in_bounds.in_set(GameSet::Tracking),
+                player_jumping.in_set(GameSet::Forces),
+                expire_surface_modifiers.in_set(GameSet::Cleanup),
+                kill_player_on_fall
+                    .in_set(GameSet::Damage)
+                    .after(hazard::kill_player_on_damage),
+            ),
+        );
+}
+
+#[derive(Component, Debug)]
+pub struct Player;
+impl Player {
+    /// How far below the screen height the player dies. Platforms are despawned around
+    /// there (see [`DespawnBelowCamera`](super::DespawnBelowCamera)), so there's nothing left
+    /// to land on.
+    const FALL_DEATH_DEPTH: f32 = 1000.0;
+
+    fn spawn(mut commands: Commands, asset_server: Res<AssetServer>, tuning: Res<GameTuning>) {
+        let sprite_bundle = SpriteBundle {
+            texture: asset_server.load("images/guy.png"),
+            ..default()
+        };
+        let ghost_sprite_bundle = SpriteBundle {
+            texture: sprite_bundle.texture.clone(),
+            visibility: Visibility::Hidden,
+            ..default()
+        };
+        commands
+            .spawn((
+                Player,
+                ColliderFromImage::aabb(),
+                Velocity(tuning.spawn_velocity),
+                tuning.player_speed_limits(),
+                KinematicController::default(),
+                RenderInterpolation::default(),
+                sprite_bundle,
+                StateScoped(Screen::InGame),
+            ))
+            .with_children(|children| {
+                children.spawn((Name::new("Wrap ghost"), WrapGhost, ghost_sprite_bundle));
+            });
+    }
+}
+
+fn player_horizontal_control(
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+    steering: Res<Steering>,
+    mut player_query: Query<(&mut Velocity, Option<&SurfaceModifier>), With<Player>>,
+) {
+    let Ok((mut player_velocity, modifier)) = player_query.get_single_mut() else {
+        return;
+    };
+    if steering.0 == 0.0 {
+        return;
+    }
+    let acceleration =
+        tuning.horizontal_acceleration * modifier.map_or(1.0, |modifier| modifier.acceleration);
+    player_velocity.x += acceleration * steering.0 * time.delta_seconds();
+}
+
+/// Changes how the player steers for a while after bouncing off a special [`Surface`].
+/// Bouncing off anything else ends it early.
+#[derive(Component, Debug)]
+pub struct SurfaceModifier {
+    /// What steering acceleration is multiplied by.
+    pub acceleration: f32,
+    /// Whether running into the side of the play area keeps the player's sideways speed,
+    /// instead of stopping them.
+    pub keep_momentum: bool,
+    pub timer: Timer,
+}
+impl SurfaceModifier {
+    fn for_surface(surface: Surface, tuning: &GameTuning) -> Option<Self> {
+        match surface {
+            Surface::Ice => Some(Self {
+                acceleration: tuning.ice_acceleration,
+                keep_momentum: true,
+                timer: Timer::from_seconds(tuning.ice_seconds, TimerMode::Once),
+            }),
+            Surface::Solid | Surface::Spring => None,
+        }
+    }
+}
+
+fn expire_surface_modifiers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut modifier_query: Query<(Entity, &mut SurfaceModifier)>,
+) {
+    for (entity, mut modifier) in modifier_query.iter_mut() {
+        if modifier.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<SurfaceModifier>();
+        }
+    }
+}
+
+/// What happens when the player reaches the side of the play area.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsMode {
+    /// Stop at the edge.
+    #[default]
+    Clamp,
+    /// Come back in on the other side.
+    Wrap,
+}
+
+fn keep_player_in_bounds(
+    play_area: Res<PlayArea>,
+    bounds_mode: Res<BoundsMode>,
+    mut player_query: Query<
+        (
+            &mut Transform,
+            &Collider,
+            &mut Velocity,
+            Option<&mut RenderInterpolation>,
+            Option<&SurfaceModifier>,
+        ),
+        With<Player>,
+    >,
+) {
+    let Ok((mut player_transform, player_collider, mut player_velocity, interpolation, modifier)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    match *bounds_mode {
+        BoundsMode::Clamp => {
+            let allowed_width = (play_area.half_width - player_collider.half_extents().x).max(0.0);
+            if !(-allowed_width..=allowed_width).contains(&player_transform.translation.x) {
+                player_transform.translation.x = f32::clamp(
+                    player_transform.translation.x,
+                    -allowed_width,
+                    allowed_width,
+                );
+                if !modifier.is_some_and(|modifier| modifier.keep_momentum) {
+                    player_velocity.x = 0.0;
+                }
+            }
+        }
+        BoundsMode::Wrap => {
+            let x = player_transform.translation.x;
+            if x.abs() <= play_area.half_width {
+                return;
+            }
+            let offset = -x.signum() * play_area.half_width * 2.0;
+            player_transform.translation.x += offset;
+            // carry the interpolation over too, so it doesn't slide back across the screen
+            if let Some(mut interpolation) = interpolation {
+                interpolation.shift(Vec3::new(offset, 0.0, 0.0));
+            }
+        }
+    }
+}
+
+/// A copy of the player sprite showing the part that has wrapped around to the other side.
+#[derive(Component, Debug)]
+struct WrapGhost;
+
+fn update_wrap_ghost(
+    play_area: Res<PlayArea>,
+    bounds_mode: Res<BoundsMode>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    mut ghost_query: Query<
+        (&Parent, &mut Transform, &mut Visibility),
+        (With<WrapGhost>, Without<Player>),
+    >,
+) {
+    for (parent, mut ghost_transform, mut ghost_visibility) in ghost_query.iter_mut() {
+        let Ok((player_transform, player_collider)) = player_query.get(parent.get()) else {
+            continue;
+        };
+        let x = player_transform.translation.x;
+        let straddling = x.abs() + player_collider.half_extents().x > play_area.half_width;
+        *ghost_visibility = if *bounds_mode == BoundsMode::Wrap && straddling {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        ghost_transform.translation.x = -x.signum() * play_area.half_width * 2.0;
+    }
+}
+
+/// Sent when the player bounces off a platform.
+#[derive(Event, Debug)]
+pub struct PlatformBounced {
+    /// May be despawned by the time this is read.
+    pub platform: Entity,
+    pub position: Vec2,
+    pub surface: Surface,
+}
+
+fn player_jumping(
+    mut commands: Commands,
+    tuning: Res<GameTuning>,
+    mut bounced: EventWriter<PlatformBounced>,
+    mut player_query: Query<
+        (
+            Entity,
+            &KinematicController,
+            Option<&LastContact>,
+            &mut Velocity,
+        ),
+        With<Player>,
+    >,
+    moving_platform_query: Query<(&Line, &Interpolator), With<Platform>>,
+    bounciness_query: Query<&Bounciness, With<Platform>>,
+    conveyor_query: Query<&Conveyor, With<Platform>>,
+) {
+    let Ok((player, controller, contact, mut player_velocity)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+    if controller.grounded {
+        player_velocity.y = tuning.jump_velocity;
+        if let Some(contact) = contact {
+            if let Ok(bounciness) = bounciness_query.get(contact.platform) {
+                player_velocity.y *= bounciness.0;
+            }
+            match SurfaceModifier::for_surface(contact.surface, &tuning) {
+                Some(modifier) => commands.entity(player).insert(modifier),
+                None => commands.entity(player).remove::<SurfaceModifier>(),
+            };
+            if let Ok(conveyor) = conveyor_query.get(contact.platform) {
+                player_velocity.x += conveyor.0;
+            }
+            if let Ok((line, interpolator)) = moving_platform_query.get(contact.platform) {
+                player_velocity.x += interpolator.velocity(line).x * tuning.platform_momentum_carry;
+            }
+            bounced.send(PlatformBounced {
+                platform: contact.platform,
+                position: contact.position,
+                surface: contact.surface,
+            });
+        }
+    }
+}
+
+fn kill_player_on_fall(
+    mut commands: Commands,
+    mut died: EventWriter<PlayerDied>,
+    screen_height: Res<ScreenHeight>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+) {
+    let Ok((player_entity, player_transform)) = player_query.get_single() else {
+        return;
+    };
+    if player_transform.translation.y < screen_height.0 - Player::FALL_DEATH_DEPTH {
+        commands.entity(player_entity).despawn_recursive();
+        died.send(PlayerDied {
+            position: player_transform.translation.truncate(),
+            killer: None,
+            cause: DeathCause::Fell,
+        });
+        eprintln!("Player fell.")
+    }
+}
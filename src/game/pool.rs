@@ -0,0 +1,52 @@
+//! Reuses platforms and hazards that fell out of view instead of despawning them,
+//! so long runs don't keep creating and destroying entities.
+
+use bevy::{ecs::system::EntityCommands, prelude::*, utils::HashMap};
+
+use crate::screen::Screen;
+
+/// Which pool a recycled entity goes back to.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PoolKind {
+    Platform,
+    Spikes,
+    Enemy,
+}
+
+/// What a recycled entity keeps, so reusing it doesn't move it between archetypes more than needed.
+type PooledBundle = (
+    StateScoped<Screen>,
+    Sprite,
+    Handle<Image>,
+    Transform,
+    GlobalTransform,
+    Visibility,
+    InheritedVisibility,
+    ViewVisibility,
+);
+
+#[derive(Resource, Debug, Default)]
+pub struct EntityPool {
+    free: HashMap<PoolKind, Vec<Entity>>,
+}
+impl EntityPool {
+    /// An entity to build a `kind` on: a recycled one if there is one, otherwise a new one.
+    /// Callers insert everything the kind needs either way.
+    pub fn take<'a>(&mut self, commands: &'a mut Commands, kind: PoolKind) -> EntityCommands<'a> {
+        let mut entity_commands = match self.free.get_mut(&kind).and_then(Vec::pop) {
+            Some(entity) => commands.entity(entity),
+            None => commands.spawn_empty(),
+        };
+        entity_commands.insert(kind);
+        entity_commands
+    }
+
+    /// Strips the entity down to its sprite, hides it and keeps it for [`EntityPool::take`].
+    pub fn recycle(&mut self, commands: &mut Commands, entity: Entity, kind: PoolKind) {
+        commands
+            .entity(entity)
+            .retain::<PooledBundle>()
+            .insert(Visibility::Hidden);
+        self.free.entry(kind).or_default().push(entity);
+    }
+}
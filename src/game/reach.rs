@@ -0,0 +1,26 @@
+//! How far the player can get sideways during a jump, so platforms aren't placed out of reach.
+
+use super::GameTuning;
+
+/// The furthest sideways a platform `rise` above the one jumped from can be and still be
+/// landed on, or `None` if it's higher than a jump goes.
+///
+/// Assumes the jump starts without sideways speed, which is the worst case short of moving
+/// the wrong way.
+pub fn max_horizontal_reach(tuning: &GameTuning, rise: f32) -> Option<f32> {
+    let (jump_speed, gravity) = (tuning.jump_velocity, tuning.gravity);
+    let discriminant = jump_speed * jump_speed - 2.0 * gravity * rise;
+    if discriminant < 0.0 {
+        return None;
+    }
+    // platforms are landed on from above, so on the way down: the later of the two times the
+    // jump passes that height
+    let air_time = (jump_speed + discriminant.sqrt()) / gravity;
+    let (acceleration, max_speed) = (tuning.horizontal_acceleration, tuning.max_horizontal_speed);
+    let time_to_max_speed = max_speed / acceleration;
+    Some(if air_time <= time_to_max_speed {
+        acceleration * air_time * air_time / 2.0
+    } else {
+        max_speed * max_speed / (2.0 * acceleration) + max_speed * (air_time - time_to_max_speed)
+    })
+}
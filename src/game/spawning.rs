@@ -0,0 +1,247 @@
+//! Placing platforms and hazards ahead of the player, and clearing away what they left behind.
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use super::{
+    director::PacingDirector, DamageSource, EntityPool, GameRng, GameSet, GameTuning,
+    GenerationConfig, HazardSpawned, Platform, PlatformKind, PlatformSpawned, PoolKind,
+    ScreenHeight, WorldOrigin,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LastPlatformSpawnHeight>()
+        .init_resource::<LastPlatformX>()
+        .add_systems(
+            FixedUpdate,
+            (
+                platform_spawner.in_set(GameSet::Spawning),
+                despawn_below_camera.in_set(GameSet::Cleanup),
+            ),
+        );
+}
+
+/// Height of the last platform in the run's terms (see [`WorldOrigin`]), so rebasing doesn't
+/// have to move it. Kept as `f64` so adding up thousands of platform distances doesn't drift.
+#[derive(Resource, Debug, Default)]
+pub struct LastPlatformSpawnHeight(pub f64);
+
+#[derive(Resource, Debug, Default)]
+pub struct LastPlatformX(pub f32);
+
+/// Despawns the entity once it is this far below the screen height, where it can't come back
+/// into view.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct DespawnBelowCamera(pub f32);
+impl Default for DespawnBelowCamera {
+    fn default() -> Self {
+        Self(1000.0)
+    }
+}
+
+fn despawn_below_camera(
+    mut commands: Commands,
+    screen_height: Res<ScreenHeight>,
+    mut pool: ResMut<EntityPool>,
+    query: Query<(Entity, &Transform, &DespawnBelowCamera, Option<&PoolKind>)>,
+) {
+    for (entity, transform, despawn_below, pool_kind) in query.iter() {
+        if transform.translation.y >= screen_height.0 - despawn_below.0 {
+            continue;
+        }
+        match pool_kind {
+            Some(&kind) => pool.recycle(&mut commands, entity, kind),
+            None => commands.entity(entity).despawn_recursive(),
+        }
+    }
+}
+
+/// The most platforms placed in a single step, so one can't take arbitrarily long.
+const MAX_PLATFORMS_PER_STEP: usize = 64;
+
+fn platform_spawner(
+    mut commands: Commands,
+    screen_height: Res<ScreenHeight>,
+    asset_server: Res<AssetServer>,
+    mut last_platform_spawn_height: ResMut<LastPlatformSpawnHeight>,
+    mut last_platform_x: ResMut<LastPlatformX>,
+    mut platform_spawned: EventWriter<PlatformSpawned>,
+    mut hazard_spawned: EventWriter<HazardSpawned>,
+    director: Res<PacingDirector>,
+    origin: Res<WorldOrigin>,
+    generation: Res<GenerationConfig>,
+    tuning: Res<GameTuning>,
+    mut rng: ResMut<GameRng>,
+    mut pool: ResMut<EntityPool>,
+    platform_query: Query<(), With<Platform>>,
+) {
+    // the first platform of a run is kept free of hazards
+    let mut non_initial = !platform_query.is_empty();
+    let spawn_top = f64::from(origin.height(screen_height.0 + tuning.spawn_bounds));
+    let spacing = f64::from(tuning.platform_spacing());
+    let mut placed = 0;
+    // a far leap (or a mistuned spawn bound) is caught up with over the next steps
+    while spawn_top >= last_platform_spawn_height.0 + spacing && placed < MAX_PLATFORMS_PER_STEP {
+        placed += 1;
+        let previous = Vec2::new(
+            last_platform_x.0,
+            origin.world_height(last_platform_spawn_height.0),
+        );
+        // stepping from the last platform instead of the screen keeps the layout the same
+        // however the player climbs, so a seed always lays out the same run
+        last_platform_spawn_height.0 += spacing;
+        let spawn_height = origin.world_height(last_platform_spawn_height.0);
+        let kind = if non_initial {
+            generation.platform_kind(last_platform_spawn_height.0 as f32, &mut **rng)
+        } else {
+            PlatformKind::Solid
+        };
+        let spawned = Platform::spawn_single(
+            commands.reborrow(),
+            &mut pool,
+            Res::clone(&asset_server),
+            &generation,
+            &tuning,
+            &mut rng,
+            previous,
+            spawn_height,
+            kind,
+        );
+        let x = spawned.position.x;
+        last_platform_x.0 = x;
+        platform_spawned.send(spawned);
+        if non_initial {
+            let offset = generation.hazard_height.sample(&mut **rng);
+            if rng.gen_bool(director.spike_chance) {
+                // chance for platform to have a small spike somewhere on it
+                hazard_spawned.send(DamageSource::spawn_spikes(
+                    commands.reborrow(),
+                    &mut pool,
+                    Res::clone(&asset_server),
+                    Vec2::new(x, spawn_height + offset),
+                ));
+            }
+
+            if rng.gen_bool(director.enemy_chance) {
+                // chance to spawn an enemy above the platform somewhere
+                hazard_spawned.send(DamageSource::spawn_enemy(
+                    commands.reborrow(),
+                    &mut pool,
+                    Res::clone(&asset_server),
+                    &generation,
+                    &tuning,
+                    &mut rng,
+                    spawn_height + offset,
+                ));
+            }
+        } else {
+            non_initial = true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::*;
+    use crate::game::{director::DirectorConfig, PlayArea};
+
+    /// Where each platform of a run seeded with `seed` goes and what kind it is, with the
+    /// screen climbing through `heights`, after `setup` has had its way with the app.
+    fn layout(
+        seed: u64,
+        heights: &[f32],
+        setup: impl FnOnce(&mut App),
+    ) -> Vec<(Vec2, PlatformKind)> {
+        #[derive(Resource, Default)]
+        struct Placed(Vec<(Vec2, PlatformKind)>);
+
+        fn record(mut spawned: EventReader<PlatformSpawned>, mut placed: ResMut<Placed>) {
+            placed.0.extend(
+                spawned
+                    .read()
+                    .map(|spawned| (spawned.position, spawned.kind)),
+            );
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .add_event::<PlatformSpawned>()
+            .add_event::<HazardSpawned>()
+            .insert_resource(GameRng::new(seed))
+            .init_resource::<GameTuning>()
+            .init_resource::<DirectorConfig>()
+            .init_resource::<PacingDirector>()
+            .init_resource::<ScreenHeight>()
+            .init_resource::<LastPlatformSpawnHeight>()
+            .init_resource::<LastPlatformX>()
+            .init_resource::<PlayArea>()
+            .init_resource::<WorldOrigin>()
+            .init_resource::<GenerationConfig>()
+            .init_resource::<EntityPool>()
+            .init_resource::<Placed>()
+            .add_systems(Update, (platform_spawner, record).chain());
+        setup(&mut app);
+        for &height in heights {
+            app.world_mut().resource_mut::<ScreenHeight>().0 = height;
+            app.update();
+        }
+        std::mem::take(&mut app.world_mut().resource_mut::<Placed>().0)
+    }
+
+    #[test]
+    fn same_seed_same_layout() {
+        let steady: Vec<f32> = (0..=200).map(|step| step as f32 * 25.0).collect();
+        let leaps = [0.0, 1200.0, 1250.0, 4000.0, 5000.0];
+        let expected = layout(42, &steady, |_| {});
+        assert!(expected.len() > 20);
+        assert_eq!(layout(42, &steady, |_| {}), expected);
+        // however the player climbs
+        assert_eq!(layout(42, &leaps, |_| {}), expected);
+        assert_ne!(layout(43, &steady, |_| {}), expected);
+    }
+
+    #[test]
+    fn layout_ignores_window_and_images() {
+        let steady: Vec<f32> = (0..=200).map(|step| step as f32 * 25.0).collect();
+        let expected = layout(42, &steady, |_| {});
+        let narrow = layout(42, &steady, |app| {
+            app.insert_resource(PlayArea { half_width: 200.0 });
+        });
+        assert_eq!(narrow, expected);
+        // platform images far wider than the real ones, as if they had loaded
+        #[derive(Resource)]
+        struct Loaded {
+            _handles: Vec<Handle<Image>>,
+        }
+        let loaded = layout(42, &steady, |app| {
+            let world = app.world_mut();
+            let handles: Vec<Handle<Image>> = [PlatformKind::Solid, PlatformKind::Spring]
+                .map(|kind| world.resource::<AssetServer>().load(kind.image()))
+                .into();
+            let mut images = world.resource_mut::<Assets<Image>>();
+            for handle in &handles {
+                let size = Extent3d {
+                    width: 400,
+                    height: 32,
+                    depth_or_array_layers: 1,
+                };
+                let image = Image::new_fill(
+                    size,
+                    TextureDimension::D2,
+                    &[255; 4],
+                    TextureFormat::Rgba8UnormSrgb,
+                    RenderAssetUsages::default(),
+                );
+                images.insert(handle, image);
+            }
+            world.insert_resource(Loaded { _handles: handles });
+        });
+        assert_eq!(loaded, expected);
+    }
+}
@@ -0,0 +1,171 @@
+//! Movement and spawning numbers, loaded from `assets/tuning.ron` so they can be changed without
+//! recompiling. With the `file_watcher` feature (on in native dev builds), saving the file
+//! applies it to the running game.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{Draft, Player, SpeedLimits};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<GameTuning>()
+        .register_asset_loader(TuningLoader)
+        .init_resource::<GameTuning>()
+        .add_systems(Startup, load_tuning)
+        .add_systems(
+            Update,
+            apply_tuning.run_if(on_event::<AssetEvent<GameTuning>>()),
+        );
+}
+
+/// Starts out with the built-in values, which are replaced once the file has loaded. Fields
+/// missing from the file keep their built-in value.
+#[derive(Asset, Resource, TypePath, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct GameTuning {
+    /// Upward speed a bounce gives.
+    pub jump_velocity: f32,
+    /// Downward acceleration, before any [`GravityScale`](super::GravityScale).
+    pub gravity: f32,
+    /// How fast steering changes sideways speed.
+    pub horizontal_acceleration: f32,
+    /// The player's [`SpeedLimits`].
+    pub max_horizontal_speed: f32,
+    pub max_fall_speed: f32,
+    /// Velocity the player starts a run with.
+    pub spawn_velocity: Vec2,
+    /// Vertical distance between platforms.
+    pub platform_distance: f32,
+    /// How far above the screen height platforms are placed.
+    pub spawn_bounds: f32,
+    /// How much of the theoretical jumping reach platforms are placed within, leaving room
+    /// for imperfect steering.
+    pub reach_margin: f32,
+    /// How long an enemy takes to travel its path one way.
+    pub enemy_crossing_seconds: f32,
+    /// How long a moving platform takes to travel its path one way.
+    pub platform_crossing_seconds: f32,
+    /// How much of a moving platform's sideways speed bouncing off it adds to the player's.
+    pub platform_momentum_carry: f32,
+    /// How long a blinking platform stays, before it phases out.
+    pub platform_solid_seconds: f32,
+    /// How long a blinking platform stays gone.
+    pub platform_gone_seconds: f32,
+    /// The [`Bounciness`](super::Bounciness) of spring platforms.
+    pub spring_jump_multiplier: f32,
+    /// What bouncing off ice multiplies the player's steering acceleration by.
+    pub ice_acceleration: f32,
+    /// How long steering stays slippery after bouncing off ice.
+    pub ice_seconds: f32,
+    /// Sideways speed a conveyor platform adds to every bounce.
+    pub conveyor_speed: f32,
+}
+impl Default for GameTuning {
+    fn default() -> Self {
+        Self {
+            jump_velocity: 575.0,
+            gravity: 225.0,
+            horizontal_acceleration: 550.0,
+            max_horizontal_speed: 460.0,
+            max_fall_speed: 700.0,
+            spawn_velocity: Vec2::new(0.0, 550.0),
+            platform_distance: 175.0,
+            spawn_bounds: 128.0,
+            reach_margin: 0.75,
+            enemy_crossing_seconds: 1.25,
+            platform_crossing_seconds: 2.0,
+            platform_momentum_carry: 0.5,
+            platform_solid_seconds: 2.0,
+            platform_gone_seconds: 1.25,
+            spring_jump_multiplier: 2.5,
+            ice_acceleration: 0.3,
+            ice_seconds: 1.0,
+            conveyor_speed: 250.0,
+        }
+    }
+}
+impl GameTuning {
+    /// The shortest and longest [`Self::platform_distance`] the spawner goes by. Closer would
+    /// place platforms without end in a single step, further would leave nothing to jump to.
+    pub const PLATFORM_DISTANCE_RANGE: (f32, f32) = (16.0, 2_000.0);
+
+    /// [`Self::platform_distance`], kept within [`Self::PLATFORM_DISTANCE_RANGE`].
+    pub fn platform_spacing(&self) -> f32 {
+        let (min, max) = Self::PLATFORM_DISTANCE_RANGE;
+        // unlike `clamp`, `max` and `min` also replace a NaN
+        self.platform_distance.max(min).min(max)
+    }
+
+    /// How long a bounce takes to carry the player onto the next platform up, landing on it
+    /// on the way down. A platform per bounce is the steady pace of a run.
+    pub fn platform_climb_seconds(&self) -> f32 {
+        let gravity = self.gravity.max(1.0);
+        // a platform out of reach takes as long as a whole bounce to not get to
+        let rise = (self.jump_velocity.powi(2) - 2.0 * gravity * self.platform_spacing()).max(0.0);
+        (self.jump_velocity + rise.sqrt()) / gravity
+    }
+
+    pub fn player_speed_limits(&self) -> SpeedLimits {
+        SpeedLimits {
+            horizontal: self.max_horizontal_speed,
+            fall: self.max_fall_speed,
+        }
+    }
+}
+
+#[derive(Default)]
+struct TuningLoader;
+impl AssetLoader for TuningLoader {
+    type Asset = GameTuning;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<GameTuning, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Keeps the file loaded, so changes to it are picked up.
+#[derive(Resource, Debug)]
+struct TuningHandle(Handle<GameTuning>);
+
+fn load_tuning(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TuningHandle(asset_server.load("tuning.ron")));
+}
+
+fn apply_tuning(
+    mut events: EventReader<AssetEvent<GameTuning>>,
+    handle: Res<TuningHandle>,
+    assets: Res<Assets<GameTuning>>,
+    mut tuning: ResMut<GameTuning>,
+    draft: Res<Draft>,
+    mut player_query: Query<&mut SpeedLimits, With<Player>>,
+) {
+    let changed = events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0));
+    let Some(loaded) = assets.get(&handle.0).filter(|_| changed) else {
+        return;
+    };
+    *tuning = loaded.clone();
+    // everything else reads the tuning as it goes, but the player's limits were set on spawn,
+    // and the run's upgrades still apply on top
+    for mut speed_limits in player_query.iter_mut() {
+        *speed_limits = draft.modifiers.speed_limits(&tuning);
+    }
+    eprintln!("Applied tuning.ron");
+}
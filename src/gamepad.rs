@@ -0,0 +1,90 @@
+//! Reading gamepads, for the analog stick and for button [`Binding`](crate::input::Binding)s.
+//!
+//! Every connected gamepad is read, so plugging one in or out mid-run just works.
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent},
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GamepadSteering>().add_systems(
+        Update,
+        log_gamepad_connections.run_if(on_event::<GamepadConnectionEvent>()),
+    );
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct GamepadSteering {
+    /// Stick deflection below this is ignored, so worn sticks don't drift the player.
+    pub deadzone: f32,
+}
+impl Default for GamepadSteering {
+    fn default() -> Self {
+        Self { deadzone: 0.15 }
+    }
+}
+
+/// The state of all connected gamepads.
+#[derive(SystemParam)]
+pub struct GamepadInput<'w> {
+    gamepads: Res<'w, Gamepads>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+    buttons: Res<'w, ButtonInput<GamepadButton>>,
+    steering: Res<'w, GamepadSteering>,
+}
+impl GamepadInput<'_> {
+    /// How far left sticks are pushed sideways, from -1 to 1, outside of the deadzone.
+    pub fn stick_x(&self) -> f32 {
+        let deadzone = self.steering.deadzone.clamp(0.0, 0.99);
+        self.gamepads
+            .iter()
+            .map(|gamepad| {
+                let stick = self
+                    .axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                    .unwrap_or(0.0);
+                // rescaled so steering starts from zero at the edge of the deadzone
+                let beyond_deadzone = (stick.abs() - deadzone).max(0.0) / (1.0 - deadzone);
+                beyond_deadzone.copysign(stick)
+            })
+            .sum::<f32>()
+            .clamp(-1.0, 1.0)
+    }
+
+    /// Whether the button was pressed this frame on any gamepad.
+    pub fn just_pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.gamepads.iter().any(|gamepad| {
+            self.buttons
+                .just_pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    /// Whether the button is held on any gamepad.
+    pub fn pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.gamepads.iter().any(|gamepad| {
+            self.buttons
+                .pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+}
+
+/// Run condition for a button being pressed on any gamepad, for input that isn't an
+/// [`Action`](crate::input::Action).
+pub fn gamepad_just_pressed(button_type: GamepadButtonType) -> impl Fn(GamepadInput) -> bool {
+    move |input| input.just_pressed(button_type)
+}
+
+fn log_gamepad_connections(mut connections: EventReader<GamepadConnectionEvent>) {
+    for event in connections.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                eprintln!("Gamepad {} connected: {}", event.gamepad.id, info.name)
+            }
+            GamepadConnection::Disconnected => {
+                eprintln!("Gamepad {} disconnected", event.gamepad.id)
+            }
+        }
+    }
+}
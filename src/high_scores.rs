@@ -0,0 +1,145 @@
+//! The local top-10 tables of runs, one per mode, saved between sessions.
+
+use bevy::{prelude::*, utils::SystemTime};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{daily_seed, GameConfig, GameMode, GameRng, PlayerDied, Score, TimeRanOut},
+    persistence::Saves,
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(
+        app.world()
+            .resource::<Saves>()
+            .load::<HighScores>(HighScores::FILE_NAME)
+            .unwrap_or_default(),
+    )
+    // a run that doesn't finish, like one quit from the pause menu, records nothing
+    .add_systems(OnEnter(Screen::InGame), forget_last_rank)
+    .add_systems(
+        Update,
+        record_high_score.run_if(on_event::<PlayerDied>().or_else(on_event::<TimeRanOut>())),
+    );
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+pub struct HighScores {
+    /// Endless runs, best first.
+    pub entries: Vec<HighScoreEntry>,
+    /// Today's daily challenge runs, best first. Earlier days are dropped when a new day's
+    /// run is recorded.
+    #[serde(default)]
+    pub daily: Vec<HighScoreEntry>,
+    /// Blitz runs, best first.
+    #[serde(default)]
+    pub blitz: Vec<HighScoreEntry>,
+    /// Marathon runs, best first.
+    #[serde(default)]
+    pub marathon: Vec<HighScoreEntry>,
+    /// Where the last finished run placed in its mode's table, if it made it at all.
+    #[serde(skip)]
+    pub last_rank: Option<usize>,
+}
+impl HighScores {
+    pub const MAX_ENTRIES: usize = 10;
+    const FILE_NAME: &'static str = "high_scores.ron";
+
+    pub fn table(&self, mode: GameMode) -> &[HighScoreEntry] {
+        match mode {
+            GameMode::Endless => &self.entries,
+            GameMode::Daily => &self.daily,
+            GameMode::Blitz => &self.blitz,
+            GameMode::Marathon => &self.marathon,
+        }
+    }
+
+    /// Inserts the entry in score order into the mode's table, returning its rank if it made
+    /// the table.
+    pub fn insert(&mut self, mode: GameMode, entry: HighScoreEntry) -> Option<usize> {
+        let table = match mode {
+            GameMode::Endless => &mut self.entries,
+            GameMode::Daily => {
+                self.daily.retain(|daily| daily.seed == entry.seed);
+                &mut self.daily
+            }
+            GameMode::Blitz => &mut self.blitz,
+            GameMode::Marathon => &mut self.marathon,
+        };
+        let rank = table
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(table.len());
+        if rank >= Self::MAX_ENTRIES {
+            return None;
+        }
+        table.insert(rank, entry);
+        table.truncate(Self::MAX_ENTRIES);
+        Some(rank)
+    }
+
+    /// Today's daily challenge table, empty if nobody has played today's yet.
+    pub fn todays_daily(&self) -> &[HighScoreEntry] {
+        let today = Some(daily_seed());
+        if self.daily.first().is_some_and(|entry| entry.seed == today) {
+            &self.daily
+        } else {
+            &[]
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HighScoreEntry {
+    pub score: u32,
+    pub height: f32,
+    /// Seconds since the Unix epoch.
+    pub date: u64,
+    pub seed: Option<u64>,
+}
+impl HighScoreEntry {
+    /// The date as `YYYY-MM-DD` (UTC).
+    pub fn date_string(&self) -> String {
+        // days to civil date, from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = (self.date / 86_400) as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+fn forget_last_rank(mut high_scores: ResMut<HighScores>) {
+    high_scores.last_rank = None;
+}
+
+fn record_high_score(
+    score: Res<Score>,
+    config: Res<GameConfig>,
+    rng: Res<GameRng>,
+    saves: Res<Saves>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let date = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let rank = high_scores.insert(
+        config.mode,
+        HighScoreEntry {
+            score: score.total(),
+            height: score.height,
+            date,
+            seed: Some(rng.seed),
+        },
+    );
+    high_scores.last_rank = rank;
+    if rank.is_some() {
+        saves.save(HighScores::FILE_NAME, &*high_scores);
+    }
+}
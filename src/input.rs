@@ -0,0 +1,278 @@
+//! What the player can do, and which keys and buttons do it.
+//!
+//! Raw keyboard, gamepad and touch input is turned into [`Action`]s once per frame, so the rest
+//! of the game reads a `ButtonInput<Action>` (and run conditions like
+//! `input_just_pressed(Action::Pause)`) without caring what is bound to what.
+
+use std::fmt;
+
+use bevy::{input::InputSystem, prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{gamepad::GamepadInput, persistence::Saves, touch::TouchInput};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(
+        app.world()
+            .resource::<Saves>()
+            .load::<ActionBindings>(ActionBindings::FILE_NAME)
+            .map_or_else(
+                ActionBindings::default,
+                ActionBindings::with_missing_defaults,
+            ),
+    )
+    .init_resource::<ButtonInput<Action>>()
+    .init_resource::<Steering>()
+    .add_systems(
+        PreUpdate,
+        (update_actions, update_steering).chain().after(InputSystem),
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    /// Start a run from a menu. Touching the screen anywhere counts too.
+    Confirm,
+    Pause,
+    Restart,
+    /// Leave the current screen for the one before it.
+    Back,
+    /// Give up on a paused run.
+    Quit,
+}
+impl Action {
+    pub const ALL: [Action; 7] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Confirm,
+        Action::Pause,
+        Action::Restart,
+        Action::Back,
+        Action::Quit,
+    ];
+
+    /// Actions in the same context can be used on the same screen, so they can't share bindings.
+    fn context(self) -> ActionContext {
+        match self {
+            Action::MoveLeft | Action::MoveRight | Action::Pause | Action::Quit => {
+                ActionContext::Playing
+            }
+            Action::Confirm | Action::Restart | Action::Back => ActionContext::Menus,
+        }
+    }
+}
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Confirm => "Confirm",
+            Action::Pause => "Pause",
+            Action::Restart => "Restart",
+            Action::Back => "Back",
+            Action::Quit => "Quit run",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ActionContext {
+    Playing,
+    Menus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    /// A button on any connected gamepad.
+    Gamepad(GamepadButtonType),
+}
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                // `KeyA` reads better as just `A`, `Digit1` as `1`
+                let name = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name);
+                f.write_str(name)
+            }
+            Binding::Gamepad(button_type) => write!(f, "Pad {button_type:?}"),
+        }
+    }
+}
+
+/// Keys and buttons bound to each action. Any number of them can be bound to one action,
+/// and the same one to several actions, as long as they're used on different screens.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct ActionBindings {
+    /// Actions left unbound are in here with no bindings, actions missing from it haven't
+    /// been saved yet.
+    pub bindings: HashMap<Action, Vec<Binding>>,
+    #[serde(default)]
+    pub opposite_presses: OppositePresses,
+}
+impl Default for ActionBindings {
+    fn default() -> Self {
+        use Binding::{Gamepad, Key};
+        Self {
+            bindings: HashMap::from_iter([
+                (
+                    Action::MoveLeft,
+                    vec![
+                        Key(KeyCode::KeyA),
+                        Key(KeyCode::ArrowLeft),
+                        Gamepad(GamepadButtonType::DPadLeft),
+                    ],
+                ),
+                (
+                    Action::MoveRight,
+                    vec![
+                        Key(KeyCode::KeyD),
+                        Key(KeyCode::ArrowRight),
+                        Gamepad(GamepadButtonType::DPadRight),
+                    ],
+                ),
+                (
+                    Action::Confirm,
+                    vec![
+                        Key(KeyCode::Space),
+                        Key(KeyCode::Enter),
+                        Gamepad(GamepadButtonType::South),
+                        Gamepad(GamepadButtonType::Start),
+                    ],
+                ),
+                (
+                    Action::Pause,
+                    vec![
+                        Key(KeyCode::Escape),
+                        Key(KeyCode::KeyP),
+                        Gamepad(GamepadButtonType::Start),
+                    ],
+                ),
+                (Action::Restart, vec![Key(KeyCode::KeyR)]),
+                (
+                    Action::Back,
+                    vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::East)],
+                ),
+                (
+                    Action::Quit,
+                    vec![Key(KeyCode::KeyQ), Gamepad(GamepadButtonType::Select)],
+                ),
+            ]),
+            opposite_presses: default(),
+        }
+    }
+}
+impl ActionBindings {
+    pub const FILE_NAME: &'static str = "controls.ron";
+
+    pub fn of(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds a binding to an action, taking it away from any action it would conflict with.
+    /// Returns the actions it was taken from.
+    pub fn bind(&mut self, action: Action, binding: Binding) -> Vec<Action> {
+        let mut taken_from = Vec::new();
+        for (&other, other_bindings) in self.bindings.iter_mut() {
+            if other != action
+                && other.context() == action.context()
+                && other_bindings.contains(&binding)
+            {
+                other_bindings.retain(|&other_binding| other_binding != binding);
+                taken_from.push(other);
+            }
+        }
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        taken_from
+    }
+
+    /// Unbinds everything from an action. It stays unbound when loaded again, see
+    /// [`Self::with_missing_defaults`].
+    pub fn clear(&mut self, action: Action) {
+        self.bindings.insert(action, Vec::new());
+    }
+
+    /// Gives actions the saved bindings don't know about, like ones added since they were
+    /// saved, their default bindings.
+    pub fn with_missing_defaults(mut self) -> Self {
+        for (action, bindings) in Self::default().bindings {
+            self.bindings.entry(action).or_insert(bindings);
+        }
+        self
+    }
+}
+
+/// How to interpret left and right being held at the same time.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OppositePresses {
+    /// Neither direction wins.
+    Cancel,
+    /// The most recently pressed direction wins, so rolling from one key to the other
+    /// changes direction immediately instead of stalling.
+    #[default]
+    LastInputPriority,
+}
+
+/// How hard the player is steering this frame, from -1 (fully left) to 1 (fully right).
+/// Keys, the d-pad and touches steer fully, a stick in proportion to how far it is pushed.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct Steering(pub f32);
+
+fn update_actions(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad: GamepadInput,
+    touch: TouchInput,
+    bindings: Res<ActionBindings>,
+    mut actions: ResMut<ButtonInput<Action>>,
+) {
+    // forget last frame's presses and releases, `press` and `release` only record changes
+    actions.clear();
+    for action in Action::ALL {
+        let pressed = bindings.of(action).iter().any(|binding| match *binding {
+            Binding::Key(key) => keys.pressed(key),
+            Binding::Gamepad(button_type) => gamepad.pressed(button_type),
+        }) || (action == Action::Confirm && touch.any_held());
+        if pressed {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+}
+
+fn update_steering(
+    actions: Res<ButtonInput<Action>>,
+    gamepad: GamepadInput,
+    touch: TouchInput,
+    bindings: Res<ActionBindings>,
+    mut last_move: Local<Option<Action>>,
+    mut steering: ResMut<Steering>,
+) {
+    for action in [Action::MoveLeft, Action::MoveRight] {
+        if actions.just_pressed(action) {
+            *last_move = Some(action);
+        }
+    }
+    steering.0 = match (
+        actions.pressed(Action::MoveLeft),
+        actions.pressed(Action::MoveRight),
+    ) {
+        (false, false) => touch.steering().unwrap_or_else(|| gamepad.stick_x()),
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        (true, true) => match (bindings.opposite_presses, *last_move) {
+            (OppositePresses::LastInputPriority, Some(Action::MoveLeft)) => -1.0,
+            (OppositePresses::LastInputPriority, Some(Action::MoveRight)) => 1.0,
+            _ => 0.0,
+        },
+    };
+}
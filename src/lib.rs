@@ -1,15 +1,38 @@
 mod asset_tracking;
+#[cfg(feature = "audio")]
 pub mod audio;
-#[cfg(feature = "dev")]
+pub mod controls;
+#[cfg(feature = "devtools")]
 mod dev_tools;
 mod game;
+pub mod gamepad;
+pub mod high_scores;
+pub mod input;
+pub mod persistence;
+pub mod screen;
+pub mod settings;
+pub mod tips;
+pub mod touch;
+#[cfg(feature = "ui")]
+mod ui;
 
-use bevy::{
-    asset::AssetMetaCheck,
-    audio::{AudioPlugin, Volume},
-    prelude::*,
+pub use game::{
+    daily_seed, Blinking, BlitzClock, Bounciness, BoundsMode, CameraFollow, Collider,
+    ColliderFromImage, Conveyor, Crumbling, DamageSource, DeathCause, DespawnBelowCamera, Draft,
+    DraftChoice, EnemyAlerted, GameCamera, GameConfig, GameMode, GameRng, GameSet, GameTuning,
+    GenerationConfig, Goal, GravityScale, HazardSpawned, HeightMilestoneReached,
+    KinematicController, LastContact, LastRunEnd, Marathon, NearMiss, Objective, ObjectiveConfig,
+    ObjectiveOutcome, Objectives, PhasedOut, PlatformBounced, PlatformKind, PlatformSpawned,
+    PlatformWeight, PlayArea, PlayerDamaged, PlayerDied, RebaseConfig, RunEnd, Shape, SpeedLimits,
+    Spread, Squash, StageCleared, Surface, SurfaceModifier, Synergy, TimeRanOut, UpgradeCatalog,
+    UpgradeDef, UpgradeEffect, UpgradeModifiers, WorldOrigin,
 };
 
+#[cfg(feature = "audio")]
+use bevy::audio::{AudioPlugin, Volume};
+use bevy::{asset::AssetMetaCheck, prelude::*};
+
+/// The standalone game: window, camera and audio setup around [`JumperGame`].
 pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
@@ -24,46 +47,105 @@ impl Plugin for AppPlugin {
         app.add_systems(Startup, spawn_camera);
 
         // Add Bevy plugins.
-        app.add_plugins(
-            DefaultPlugins
-                .set(AssetPlugin {
-                    // Wasm builds will check for meta files (that don't exist) if this isn't set.
-                    // This causes errors and even panics on web build on itch.
-                    // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
-                    meta_check: AssetMetaCheck::Never,
-                    ..default()
-                })
-                .set(WindowPlugin {
-                    primary_window: Window {
-                        title: "Jumper".to_string(),
-                        canvas: Some("#bevy".to_string()),
-                        fit_canvas_to_parent: true,
-                        prevent_default_event_handling: true,
-                        ..default()
-                    }
-                    .into(),
+        let default_plugins = DefaultPlugins
+            .set(AssetPlugin {
+                // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                // This causes errors and even panics on web build on itch.
+                // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            })
+            .set(WindowPlugin {
+                primary_window: Window {
+                    title: "Jumper".to_string(),
+                    canvas: Some("#bevy".to_string()),
+                    fit_canvas_to_parent: true,
+                    prevent_default_event_handling: true,
                     ..default()
-                })
-                .set(AudioPlugin {
-                    global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
-                    },
-                    ..default()
-                }),
-        );
+                }
+                .into(),
+                ..default()
+            });
+        #[cfg(feature = "audio")]
+        let default_plugins = default_plugins.set(AudioPlugin {
+            global_volume: GlobalVolume {
+                volume: Volume::new(0.3),
+            },
+            ..default()
+        });
+        app.add_plugins(default_plugins);
 
         // Add other plugins.
-        app.add_plugins((asset_tracking::plugin, game::plugin));
+        app.add_plugins((asset_tracking::plugin, JumperGame::default()));
 
         // Enable dev tools for dev builds.
-        #[cfg(feature = "dev")]
+        #[cfg(feature = "devtools")]
         app.add_plugins(dev_tools::plugin);
     }
 }
 
-/// High-level groupings of systems for the app in the `Update` schedule.
-/// When adding a new variant, make sure to order it in the `configure_sets`
-/// call above.
+/// The game as an embeddable plugin.
+///
+/// Host apps are expected to provide [`DefaultPlugins`] and a 2D camera marked with [`GameCamera`],
+/// which the game moves to follow the player.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use jumper::{GameCamera, GameMode, JumperGame};
+///
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_systems(Startup, |mut commands: Commands| {
+///         commands.spawn((Camera2dBundle::default(), GameCamera));
+///     })
+///     .add_plugins(JumperGame::new().with_seed(42).with_mode(GameMode::Endless))
+///     .run();
+/// ```
+#[derive(Debug, Default)]
+pub struct JumperGame {
+    config: GameConfig,
+}
+impl JumperGame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Fixes the seed for level generation (see [`GameConfig::seed`]).
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.config.seed = Some(seed);
+        self
+    }
+    pub fn with_mode(mut self, mode: GameMode) -> Self {
+        self.config.mode = mode;
+        self
+    }
+}
+impl Plugin for JumperGame {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone());
+        app.add_plugins((
+            persistence::plugin,
+            gamepad::plugin,
+            input::plugin,
+            controls::plugin,
+            screen::plugin,
+            settings::plugin,
+            game::plugin,
+            high_scores::plugin,
+            tips::plugin,
+        ));
+
+        #[cfg(feature = "audio")]
+        app.add_plugins(audio::plugin);
+
+        // Menus and overlays are optional.
+        #[cfg(feature = "ui")]
+        app.add_plugins(ui::plugin);
+    }
+}
+
+// High-level groupings of systems for the app in the `Update` schedule.
+// When adding a new variant, make sure to order it in the `configure_sets`
+// call above.
 //#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 //enum AppSet {
 //    /// Tick timers.
@@ -78,12 +160,16 @@ fn spawn_camera(mut commands: Commands) {
     commands.spawn((
         Name::new("Camera"),
         Camera2dBundle::default(),
+        GameCamera,
+        // the camera follows the player in fixed steps too
+        game::RenderInterpolation::default(),
         // Render all UI to this camera.
         // Not strictly necessary since we only use one camera,
         // but if we don't use this component, our UI will disappear as soon
         // as we add another camera. This includes indirect ways of adding cameras like using
         // [ui node outlines](https://bevyengine.org/news/bevy-0-14/#ui-node-outline-gizmos)
         // for debugging. So it's good to have this here for future-proofing.
+        #[cfg(feature = "ui")]
         IsDefaultUiCamera,
     ));
 }
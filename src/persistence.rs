@@ -0,0 +1,191 @@
+//! Reading and writing save files as RON, through pluggable storage backends.
+//!
+//! Saves always go to [`LocalStorage`], the platform's config directory, and to a cloud backend
+//! too if one is set on [`Saves`]. When the two copies disagree on load, the newest one wins
+//! and the other is kept next to it as a `.bak` file.
+//!
+//! Web builds have no file system to write to, so local storage finds nothing and keeps nothing.
+
+use bevy::{prelude::*, utils::SystemTime};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    // a host app may have inserted its own, with a cloud backend
+    app.init_resource::<Saves>();
+}
+
+/// Somewhere save files can be kept.
+pub trait Storage: Send + Sync + 'static {
+    /// Reads a save file, or `None` if there is none by that name.
+    fn read(&self, file_name: &str) -> Result<Option<SaveFile>, String>;
+    fn write(&self, file_name: &str, file: &SaveFile) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveFile {
+    pub contents: String,
+    /// When the file was last saved, in seconds since the Unix epoch.
+    pub modified: u64,
+}
+
+/// Save files in the platform's config directory.
+#[derive(Debug, Default)]
+pub struct LocalStorage;
+impl Storage for LocalStorage {
+    fn read(&self, file_name: &str) -> Result<Option<SaveFile>, String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(path) = save_dir().map(|dir| dir.join(file_name)) else {
+                return Ok(None);
+            };
+            if !path.exists() {
+                return Ok(None);
+            }
+            let contents = std::fs::read_to_string(&path).map_err(|error| error.to_string())?;
+            let modified = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .map_or(0, unix_seconds);
+            Ok(Some(SaveFile { contents, modified }))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = file_name;
+            Ok(None)
+        }
+    }
+
+    fn write(&self, file_name: &str, file: &SaveFile) -> Result<(), String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let dir = save_dir().ok_or("no config directory")?;
+            std::fs::create_dir_all(&dir).map_err(|error| error.to_string())?;
+            let path = dir.join(file_name);
+            std::fs::write(&path, &file.contents).map_err(|error| error.to_string())?;
+            // the modification time is what conflicts are resolved by, so it has to match
+            let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(file.modified);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|opened| opened.set_modified(modified))
+                .map_err(|error| error.to_string())
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (file_name, file);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_dir() -> Option<std::path::PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("jumper"))
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Where the game keeps its save files.
+#[derive(Resource)]
+pub struct Saves {
+    local: Box<dyn Storage>,
+    cloud: Option<Box<dyn Storage>>,
+}
+impl Default for Saves {
+    fn default() -> Self {
+        Self {
+            local: Box::new(LocalStorage),
+            cloud: None,
+        }
+    }
+}
+impl Saves {
+    /// Also keeps saves in `cloud`, syncing it with local storage whenever a file is loaded.
+    pub fn with_cloud(self, cloud: impl Storage) -> Self {
+        Self {
+            cloud: Some(Box::new(cloud)),
+            ..self
+        }
+    }
+
+    /// Loads a save file, or `None` if it doesn't exist or can't be read.
+    pub fn load<T: DeserializeOwned>(&self, file_name: &str) -> Option<T> {
+        let file = self.sync(file_name)?;
+        match ron::from_str(&file.contents) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                warn!("Could not parse {file_name}: {error}");
+                None
+            }
+        }
+    }
+
+    /// Writes a save file, logging instead of failing since losing a save shouldn't stop the game.
+    pub fn save<T: Serialize>(&self, file_name: &str, value: &T) {
+        let contents = match ron::ser::to_string_pretty(value, default()) {
+            Ok(contents) => contents,
+            Err(error) => {
+                warn!("Could not save {file_name}: {error}");
+                return;
+            }
+        };
+        let file = SaveFile {
+            contents,
+            modified: unix_seconds(SystemTime::now()),
+        };
+        for storage in self.storages() {
+            write_or_warn(storage, file_name, &file);
+        }
+    }
+
+    /// Reads both copies of a file and brings whichever is behind up to date.
+    fn sync(&self, file_name: &str) -> Option<SaveFile> {
+        let local = read_or_warn(&*self.local, file_name);
+        let Some(cloud) = self.cloud.as_deref() else {
+            return local;
+        };
+        match (local, read_or_warn(cloud, file_name)) {
+            (Some(local_file), Some(cloud_file)) if local_file.contents != cloud_file.contents => {
+                let (newer, older, behind) = if cloud_file.modified > local_file.modified {
+                    (cloud_file, local_file, &*self.local)
+                } else {
+                    (local_file, cloud_file, cloud)
+                };
+                warn!("Local and cloud copies of {file_name} differ, keeping the newer one");
+                // in case the newer copy is the one the player didn't want
+                write_or_warn(&*self.local, &format!("{file_name}.bak"), &older);
+                write_or_warn(behind, file_name, &newer);
+                Some(newer)
+            }
+            (Some(file), Some(_)) => Some(file),
+            (Some(local_file), None) => {
+                write_or_warn(cloud, file_name, &local_file);
+                Some(local_file)
+            }
+            (None, Some(cloud_file)) => {
+                write_or_warn(&*self.local, file_name, &cloud_file);
+                Some(cloud_file)
+            }
+            (None, None) => None,
+        }
+    }
+
+    fn storages(&self) -> impl Iterator<Item = &dyn Storage> {
+        std::iter::once(&*self.local).chain(self.cloud.as_deref())
+    }
+}
+
+fn read_or_warn(storage: &dyn Storage, file_name: &str) -> Option<SaveFile> {
+    storage.read(file_name).unwrap_or_else(|error| {
+        warn!("Could not read {file_name}: {error}");
+        None
+    })
+}
+
+fn write_or_warn(storage: &dyn Storage, file_name: &str, file: &SaveFile) {
+    if let Err(error) = storage.write(file_name, file) {
+        warn!("Could not save {file_name}: {error}");
+    }
+}
@@ -0,0 +1,112 @@
+//! The game's main screen states and the transitions between them.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    game::{GameConfig, GameMode},
+    gamepad::gamepad_just_pressed,
+    input::Action,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_state::<Screen>()
+        .add_sub_state::<Pause>()
+        .enable_state_scoped_entities::<Screen>()
+        .enable_state_scoped_entities::<Pause>()
+        .add_systems(
+            Update,
+            (
+                start_run(GameMode::Endless).run_if(
+                    in_state(Screen::MainMenu).and_then(input_just_pressed(Action::Confirm)),
+                ),
+                start_run(GameMode::Daily).run_if(
+                    in_state(Screen::MainMenu).and_then(
+                        input_just_pressed(KeyCode::KeyD)
+                            .or_else(gamepad_just_pressed(GamepadButtonType::West)),
+                    ),
+                ),
+                start_run(GameMode::Blitz).run_if(
+                    in_state(Screen::MainMenu).and_then(
+                        input_just_pressed(KeyCode::KeyB)
+                            .or_else(gamepad_just_pressed(GamepadButtonType::RightTrigger)),
+                    ),
+                ),
+                start_run(GameMode::Marathon).run_if(
+                    in_state(Screen::MainMenu).and_then(
+                        input_just_pressed(KeyCode::KeyM)
+                            .or_else(gamepad_just_pressed(GamepadButtonType::LeftTrigger)),
+                    ),
+                ),
+                // fixed instead of an action, so the controls can always be fixed
+                enter(Screen::Controls).run_if(
+                    in_state(Screen::MainMenu).and_then(
+                        input_just_pressed(KeyCode::KeyC)
+                            .or_else(gamepad_just_pressed(GamepadButtonType::Select)),
+                    ),
+                ),
+                enter(Screen::Settings).run_if(
+                    in_state(Screen::MainMenu).and_then(
+                        input_just_pressed(KeyCode::KeyS)
+                            .or_else(gamepad_just_pressed(GamepadButtonType::North)),
+                    ),
+                ),
+                toggle_pause
+                    .run_if(in_state(Screen::InGame).and_then(input_just_pressed(Action::Pause))),
+                enter(Screen::InGame).run_if(
+                    in_state(Screen::GameOver).and_then(
+                        input_just_pressed(Action::Confirm)
+                            .or_else(input_just_pressed(Action::Restart)),
+                    ),
+                ),
+                enter(Screen::MainMenu)
+                    .run_if(in_state(Screen::GameOver).and_then(input_just_pressed(Action::Back))),
+            ),
+        );
+}
+
+/// Which screen the game is showing.
+/// Entities that belong to a run are scoped to [`Screen::InGame`] and despawned when it ends.
+#[derive(States, Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Screen {
+    #[default]
+    MainMenu,
+    /// Rebinding keys and buttons, see [`crate::controls`].
+    Controls,
+    /// Volumes, see [`crate::settings`].
+    Settings,
+    InGame,
+    GameOver,
+}
+
+/// Whether a run is paused. Only exists while [`Screen::InGame`] is active,
+/// so pausing keeps the run's entities around.
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+#[source(Screen = Screen::InGame)]
+pub enum Pause {
+    #[default]
+    Running,
+    Paused,
+    /// Picking an upgrade, like between two stages of a [`GameMode::Marathon`] run. See
+    /// [`crate::game::Draft`].
+    Intermission,
+}
+
+fn enter(screen: Screen) -> impl Fn(ResMut<NextState<Screen>>) {
+    move |mut next_screen| next_screen.set(screen)
+}
+
+fn start_run(mode: GameMode) -> impl Fn(ResMut<GameConfig>, ResMut<NextState<Screen>>) {
+    move |mut config, mut next_screen| {
+        config.mode = mode;
+        next_screen.set(Screen::InGame);
+    }
+}
+
+fn toggle_pause(pause: Res<State<Pause>>, mut next_pause: ResMut<NextState<Pause>>) {
+    next_pause.set(match pause.get() {
+        Pause::Running => Pause::Paused,
+        Pause::Paused => Pause::Running,
+        // an intermission ends by picking an upgrade or skipping it
+        Pause::Intermission => return,
+    });
+}
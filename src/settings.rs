@@ -0,0 +1,168 @@
+//! Player-facing settings, shared by the game and its optional subsystems, and the settings
+//! screen for changing them.
+
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{persistence::Saves, screen::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    let saves = app.world().resource::<Saves>();
+    let audio_settings = saves
+        .load::<AudioSettings>(AudioSettings::FILE_NAME)
+        .unwrap_or_default();
+    let accessibility = saves
+        .load::<Accessibility>(Accessibility::FILE_NAME)
+        .unwrap_or_default();
+    app.insert_resource(audio_settings)
+        .insert_resource(accessibility)
+        .init_resource::<Language>()
+        .add_systems(OnEnter(Screen::Settings), reset_settings_screen)
+        .add_systems(
+            Update,
+            handle_settings_input.run_if(in_state(Screen::Settings)),
+        )
+        .add_systems(OnExit(Screen::Settings), save_settings);
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Accessibility {
+    /// Turns off screen effects that flash or pulse, like the danger vignette.
+    pub reduce_motion: bool,
+}
+impl Accessibility {
+    const FILE_NAME: &'static str = "accessibility.ron";
+}
+
+/// The language text is shown in.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+}
+
+/// Volumes from 0 to 1. Music and sound effects are each scaled by the master volume.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sound_effects: f32,
+}
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 1.0,
+            sound_effects: 1.0,
+        }
+    }
+}
+impl AudioSettings {
+    const FILE_NAME: &'static str = "audio_settings.ron";
+
+    pub fn volume(&self, bus: AudioBus) -> f32 {
+        match bus {
+            AudioBus::Master => self.master,
+            AudioBus::Music => self.music,
+            AudioBus::SoundEffects => self.sound_effects,
+        }
+    }
+
+    fn volume_mut(&mut self, bus: AudioBus) -> &mut f32 {
+        match bus {
+            AudioBus::Master => &mut self.master,
+            AudioBus::Music => &mut self.music,
+            AudioBus::SoundEffects => &mut self.sound_effects,
+        }
+    }
+
+    /// How loud the bus actually plays, after the master volume.
+    pub fn output(&self, bus: AudioBus) -> f32 {
+        match bus {
+            AudioBus::Master => self.master,
+            _ => self.master * self.volume(bus),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioBus {
+    Master,
+    Music,
+    SoundEffects,
+}
+impl AudioBus {
+    pub const ALL: [AudioBus; 3] = [AudioBus::Master, AudioBus::Music, AudioBus::SoundEffects];
+}
+impl fmt::Display for AudioBus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            AudioBus::Master => "Master volume",
+            AudioBus::Music => "Music volume",
+            AudioBus::SoundEffects => "Sound effects volume",
+        })
+    }
+}
+
+/// Which row of the settings screen is selected: a volume, as an index into [`AudioBus::ALL`],
+/// or the reduce motion toggle after them.
+#[derive(Resource, Debug, Default)]
+pub struct SettingsScreen {
+    pub selected: usize,
+}
+impl SettingsScreen {
+    pub const REDUCE_MOTION_ROW: usize = AudioBus::ALL.len();
+    pub const ROWS: usize = Self::REDUCE_MOTION_ROW + 1;
+}
+
+fn reset_settings_screen(mut commands: Commands) {
+    commands.insert_resource(SettingsScreen::default());
+}
+
+/// Like the controls screen, navigated with fixed keys and buttons.
+fn handle_settings_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut accessibility: ResMut<Accessibility>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    const VOLUME_STEP: f32 = 0.1;
+    let pressed = |key, button_type| {
+        keys.just_pressed(key)
+            || gamepad_buttons
+                .get_just_pressed()
+                .any(|button| button.button_type == button_type)
+    };
+    let rows = SettingsScreen::ROWS;
+    let bus = AudioBus::ALL.get(settings_screen.selected).copied();
+    let left = pressed(KeyCode::ArrowLeft, GamepadButtonType::DPadLeft);
+    let right = pressed(KeyCode::ArrowRight, GamepadButtonType::DPadRight);
+    if pressed(KeyCode::ArrowUp, GamepadButtonType::DPadUp) {
+        settings_screen.selected = (settings_screen.selected + rows - 1) % rows;
+    } else if pressed(KeyCode::ArrowDown, GamepadButtonType::DPadDown) {
+        settings_screen.selected = (settings_screen.selected + 1) % rows;
+    } else if let (Some(bus), true) = (bus, left) {
+        let volume = audio_settings.volume_mut(bus);
+        *volume = (*volume - VOLUME_STEP).max(0.0);
+    } else if let (Some(bus), true) = (bus, right) {
+        let volume = audio_settings.volume_mut(bus);
+        *volume = (*volume + VOLUME_STEP).min(1.0);
+    } else if settings_screen.selected == SettingsScreen::REDUCE_MOTION_ROW && (left || right) {
+        accessibility.reduce_motion = !accessibility.reduce_motion;
+    } else if pressed(KeyCode::Escape, GamepadButtonType::East) {
+        next_screen.set(Screen::MainMenu);
+    }
+}
+
+fn save_settings(
+    saves: Res<Saves>,
+    audio_settings: Res<AudioSettings>,
+    accessibility: Res<Accessibility>,
+) {
+    saves.save(AudioSettings::FILE_NAME, &*audio_settings);
+    saves.save(Accessibility::FILE_NAME, &*accessibility);
+}
@@ -0,0 +1,96 @@
+//! Tips for the game over screen, picked from what has been killing the player most.
+//!
+//! Deaths are counted by cause across all runs and saved between sessions.
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{DamageSource, DeathCause, PlayerDied},
+    persistence::Saves,
+    settings::Language,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(
+        app.world()
+            .resource::<Saves>()
+            .load::<DeathStats>(DeathStats::FILE_NAME)
+            .unwrap_or_default(),
+    )
+    .add_systems(Update, record_death.run_if(on_event::<PlayerDied>()));
+}
+
+/// How many times each cause has killed the player.
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+pub struct DeathStats {
+    pub deaths: HashMap<DeathCause, u32>,
+    /// What killed the player last.
+    pub latest: Option<DeathCause>,
+}
+impl DeathStats {
+    const FILE_NAME: &'static str = "death_stats.ron";
+
+    /// The cause of most deaths, or the latest one of those on a tie.
+    pub fn most_common(&self) -> Option<DeathCause> {
+        let most = self.deaths.values().copied().max()?;
+        self.latest
+            .filter(|latest| self.deaths.get(latest) == Some(&most))
+            .or_else(|| {
+                self.deaths
+                    .iter()
+                    .find(|(_, &count)| count == most)
+                    .map(|(&cause, _)| cause)
+            })
+    }
+
+    /// The tip that would help most, going by what kills the player most often.
+    pub fn relevant_tip(&self, language: Language) -> Option<&'static str> {
+        self.most_common()
+            .map(|cause| tip_text(TipTopic::for_cause(cause), language))
+    }
+}
+
+/// What a tip is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipTopic {
+    AvoidingSpikes,
+    CloudTiming,
+    TrajectoryAssist,
+}
+impl TipTopic {
+    fn for_cause(cause: DeathCause) -> Self {
+        match cause {
+            DeathCause::Hazard(DamageSource::Spikes) => TipTopic::AvoidingSpikes,
+            DeathCause::Hazard(DamageSource::AngryCloud) => TipTopic::CloudTiming,
+            DeathCause::Fell => TipTopic::TrajectoryAssist,
+        }
+    }
+}
+
+/// The tips table, with a row for each language.
+pub fn tip_text(topic: TipTopic, language: Language) -> &'static str {
+    match (language, topic) {
+        (Language::English, TipTopic::AvoidingSpikes) => {
+            "Spikes never move, so steer around them while you're still high above"
+        }
+        (Language::English, TipTopic::CloudTiming) => {
+            "Clouds reverse at their endpoints, time your pass"
+        }
+        (Language::English, TipTopic::TrajectoryAssist) => {
+            "Press T to see where your jump will take you"
+        }
+    }
+}
+
+fn record_death(
+    mut died: EventReader<PlayerDied>,
+    saves: Res<Saves>,
+    mut stats: ResMut<DeathStats>,
+) {
+    for death in died.read() {
+        *stats.deaths.entry(death.cause).or_default() += 1;
+        stats.latest = Some(death.cause);
+    }
+    saves.save(DeathStats::FILE_NAME, &*stats);
+}
@@ -0,0 +1,31 @@
+//! Touch controls for phones and mobile browsers: hold the left or right half of the screen
+//! to steer, and tap to confirm in menus.
+//!
+//! There's nothing to switch on, devices without a touch screen just never report a touch.
+
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+
+/// The state of touches on the primary window.
+#[derive(SystemParam)]
+pub struct TouchInput<'w, 's> {
+    touches: Res<'w, Touches>,
+    window_query: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+}
+impl TouchInput<'_, '_> {
+    /// Which half of the screen is held, as -1 for left or 1 for right. With several fingers
+    /// down the newest one counts, like the last pressed key does.
+    pub fn steering(&self) -> Option<f32> {
+        let window = self.window_query.get_single().ok()?;
+        let touch = self.touches.iter().max_by_key(|touch| touch.id())?;
+        Some(if touch.position().x < window.width() / 2.0 {
+            -1.0
+        } else {
+            1.0
+        })
+    }
+
+    /// Whether any finger is on the screen.
+    pub fn any_held(&self) -> bool {
+        self.touches.iter().next().is_some()
+    }
+}
@@ -0,0 +1,67 @@
+//! The controls screen, listing every action with what is bound to it.
+
+use bevy::prelude::*;
+use itertools::Itertools;
+
+use super::widgets::{Containers, Widgets};
+use crate::{
+    controls::ControlsScreen,
+    input::{Action, ActionBindings},
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Controls), spawn_controls_screen)
+        .add_systems(
+            Update,
+            update_controls_screen.run_if(in_state(Screen::Controls).and_then(
+                resource_changed::<ControlsScreen>.or_else(resource_changed::<ActionBindings>),
+            )),
+        );
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+enum ControlsText {
+    Action(usize),
+    Message,
+}
+
+fn spawn_controls_screen(mut commands: Commands) {
+    commands
+        .ui_root()
+        .insert((Name::new("Controls screen"), StateScoped(Screen::Controls)))
+        .with_children(|children| {
+            children.header("Controls");
+            for index in 0..Action::ALL.len() {
+                children.label("").insert(ControlsText::Action(index));
+            }
+            children.label("").insert(ControlsText::Message);
+            children.label("Up/Down to choose, Enter or (A) to add a binding");
+            children.label("Backspace or (X) to clear, Escape or (B) to go back");
+        });
+}
+
+fn update_controls_screen(
+    controls: Res<ControlsScreen>,
+    bindings: Res<ActionBindings>,
+    mut text_query: Query<(&mut Text, &ControlsText)>,
+) {
+    for (mut text, controls_text) in text_query.iter_mut() {
+        text.sections[0].value = match *controls_text {
+            ControlsText::Action(index) => {
+                let action = Action::ALL[index];
+                let bound = match bindings.of(action) {
+                    [] => "(unbound)".to_string(),
+                    bound => bound.iter().join(", "),
+                };
+                let marker = if index == controls.selected { "> " } else { "" };
+                format!("{marker}{action}: {bound}")
+            }
+            ControlsText::Message if controls.capturing => format!(
+                "Press a key or button for {} (Escape to cancel)",
+                controls.selected_action()
+            ),
+            ControlsText::Message => controls.message.clone().unwrap_or_default(),
+        };
+    }
+}
@@ -0,0 +1,102 @@
+//! The in-game HUD showing height, score, the current objective, the blitz clock and the
+//! run's seed.
+
+use bevy::prelude::*;
+
+use super::widgets::Widgets;
+use crate::{
+    game::{BlitzClock, GameConfig, GameMode, GameRng, Objectives, Player, Score, WorldOrigin},
+    screen::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::InGame), spawn_hud)
+        .add_systems(Update, update_hud.run_if(in_state(Screen::InGame)));
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+enum HudText {
+    Height,
+    BestHeight,
+    Score,
+    Objective,
+    BlitzClock,
+    Seed,
+}
+
+fn spawn_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("HUD"),
+            StateScoped(Screen::InGame),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|children| {
+            for hud_text in [
+                HudText::Height,
+                HudText::BestHeight,
+                HudText::Score,
+                HudText::Objective,
+                HudText::BlitzClock,
+                HudText::Seed,
+            ] {
+                children.label("").insert(hud_text);
+            }
+        });
+}
+
+fn update_hud(
+    score: Res<Score>,
+    origin: Res<WorldOrigin>,
+    objectives: Res<Objectives>,
+    rng: Res<GameRng>,
+    config: Res<GameConfig>,
+    blitz_clock: Res<BlitzClock>,
+    player_query: Query<&Transform, With<Player>>,
+    mut text_query: Query<(&mut Text, &HudText)>,
+) {
+    let height = player_query.get_single().map_or(score.height, |transform| {
+        origin.height(transform.translation.y)
+    });
+    for (mut text, hud_text) in text_query.iter_mut() {
+        text.sections[0].value = match hud_text {
+            HudText::Height => format!("Height: {:.0}", height.max(0.0)),
+            HudText::BestHeight => format!("Best: {:.0}", score.height.max(0.0)),
+            HudText::Score => format!("Score: {}", score.total()),
+            HudText::Objective => objective_text(&objectives),
+            HudText::BlitzClock if config.mode == GameMode::Blitz => {
+                let seconds = blitz_clock.seconds_left().ceil() as u32;
+                format!("Time: {}:{:02}", seconds / 60, seconds % 60)
+            }
+            HudText::BlitzClock => String::new(),
+            HudText::Seed => format!("Seed: {}", rng.seed),
+        };
+    }
+}
+
+fn objective_text(objectives: &Objectives) -> String {
+    if let Some(objective) = &objectives.active {
+        return format!(
+            "Goal: {} ({:.0}/{:.0}, {:.0}s left)",
+            objective.goal,
+            objective.progress.clamp(0.0, objective.target()),
+            objective.target(),
+            objective.seconds_left.max(0.0).ceil()
+        );
+    }
+    match &objectives.last_outcome {
+        Some(outcome) => match outcome.reward {
+            Some(reward) => format!("Goal complete: {} (+{reward})", outcome.goal),
+            None => format!("Goal failed: {}", outcome.goal),
+        },
+        None => String::new(),
+    }
+}
@@ -0,0 +1,187 @@
+//! Overlays for the main menu, pause, upgrade draft and game over screens.
+
+use bevy::prelude::*;
+
+use super::widgets::{Containers, Widgets};
+use crate::{
+    game::{
+        DamageSource, DeathCause, Draft, DraftChoice, GameConfig, GameMode, GameRng, LastRunEnd,
+        Marathon, RunEnd, Score,
+    },
+    high_scores::{HighScoreEntry, HighScores},
+    screen::{Pause, Screen},
+    settings::Language,
+    tips::DeathStats,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::MainMenu), spawn_main_menu)
+        .add_systems(OnEnter(Pause::Paused), spawn_pause_menu)
+        .add_systems(OnEnter(Pause::Intermission), spawn_draft_screen)
+        .add_systems(OnEnter(Screen::GameOver), spawn_game_over_screen)
+        .add_systems(
+            Update,
+            tap_draft_choices.run_if(in_state(Pause::Intermission)),
+        );
+}
+
+fn spawn_main_menu(mut commands: Commands, high_scores: Res<HighScores>) {
+    commands
+        .ui_root()
+        .insert((Name::new("Main menu"), StateScoped(Screen::MainMenu)))
+        .with_children(|children| {
+            children.header("Jumper");
+            children.label("Press Space, (A) or tap to play");
+            children.label("Press D or (X) for the daily challenge");
+            children.label("Press B or (RB) for a two minute blitz");
+            children.label("Press M or (LB) for a marathon in stages");
+            children.label("Press C or Select for controls");
+            children.label("Press S or (Y) for settings");
+            high_score_table(children, "High scores", &high_scores.entries);
+            high_score_table(
+                children,
+                "Today's daily challenge",
+                high_scores.todays_daily(),
+            );
+            high_score_table(children, "Blitz high scores", &high_scores.blitz);
+            high_score_table(children, "Marathon high scores", &high_scores.marathon);
+        });
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    commands
+        .ui_root()
+        .insert((Name::new("Pause menu"), StateScoped(Pause::Paused)))
+        .with_children(|children| {
+            children.header("Paused");
+            children.label("Press Escape or Start to resume");
+            children.label("Press Q or Select to quit the run");
+        });
+}
+
+fn spawn_draft_screen(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    score: Res<Score>,
+    marathon: Res<Marathon>,
+    draft: Res<Draft>,
+) {
+    commands
+        .ui_root()
+        .insert((Name::new("Draft screen"), StateScoped(Pause::Intermission)))
+        .with_children(|children| {
+            if config.mode == GameMode::Marathon {
+                children.header(format!("Stage {} clear", marathon.stages_cleared));
+                children.label(format!("Height: {:.0}", score.height.max(0.0)));
+                children.label(format!("Score: {}", score.total()));
+                children.label(format!("Stage time: {:.1}s", marathon.last_stage_seconds));
+            }
+            children.header("Pick an upgrade");
+            for (index, ((number, button), upgrade)) in [("1", "X"), ("2", "Y"), ("3", "B")]
+                .into_iter()
+                .zip(&draft.offered)
+                .enumerate()
+            {
+                children
+                    .label(format!(
+                        "{number}, ({button}) or tap: {}, {}",
+                        upgrade.name, upgrade.description
+                    ))
+                    .insert((
+                        Interaction::default(),
+                        TappableChoice(DraftChoice::Pick(index)),
+                    ));
+            }
+            children
+                .label("Press Space, (A) or tap here to continue without one")
+                .insert((Interaction::default(), TappableChoice(DraftChoice::Skip)));
+        });
+}
+
+/// A line of the draft overlay that makes its choice when tapped or clicked.
+#[derive(Component, Debug, Clone, Copy)]
+struct TappableChoice(DraftChoice);
+
+fn tap_draft_choices(
+    choice_query: Query<(&Interaction, &TappableChoice), Changed<Interaction>>,
+    mut chosen: EventWriter<DraftChoice>,
+) {
+    for (interaction, choice) in choice_query.iter() {
+        if *interaction == Interaction::Pressed {
+            chosen.send(choice.0);
+        }
+    }
+}
+
+fn spawn_game_over_screen(
+    mut commands: Commands,
+    score: Res<Score>,
+    config: Res<GameConfig>,
+    rng: Res<GameRng>,
+    last_run_end: Res<LastRunEnd>,
+    marathon: Res<Marathon>,
+    high_scores: Res<HighScores>,
+    death_stats: Res<DeathStats>,
+    language: Res<Language>,
+) {
+    commands
+        .ui_root()
+        .insert((Name::new("Game over screen"), StateScoped(Screen::GameOver)))
+        .with_children(|children| {
+            children.header(match config.mode {
+                GameMode::Endless => "Game over",
+                GameMode::Daily => "Daily challenge over",
+                GameMode::Blitz => "Blitz over",
+                GameMode::Marathon => "Marathon over",
+            });
+            if let Some(run_end) = last_run_end.0 {
+                children.label(run_end_text(run_end));
+            }
+            if let Some(tip) = death_stats.relevant_tip(*language) {
+                children.label(format!("Tip: {tip}"));
+            }
+            children.label(format!("Height: {:.0}", score.height.max(0.0)));
+            children.label(format!("Score: {}", score.total()));
+            if config.mode == GameMode::Marathon {
+                children.label(format!("Stages cleared: {}", marathon.stages_cleared));
+            }
+            children.label(format!("Seed: {}", rng.seed));
+            if let Some(rank) = high_scores.last_rank {
+                children.label(format!("New high score: #{}!", rank + 1));
+            }
+            children.label("Press R, (A) or tap to restart, Escape or (B) for the menu");
+            let title = match config.mode {
+                GameMode::Endless => "High scores",
+                GameMode::Daily => "Today's daily challenge",
+                GameMode::Blitz => "Blitz high scores",
+                GameMode::Marathon => "Marathon high scores",
+            };
+            high_score_table(children, title, high_scores.table(config.mode));
+        });
+}
+
+fn run_end_text(run_end: RunEnd) -> &'static str {
+    match run_end {
+        RunEnd::Died(DeathCause::Hazard(DamageSource::Spikes)) => "Killed by: Spikes",
+        RunEnd::Died(DeathCause::Hazard(DamageSource::AngryCloud)) => "Killed by: Angry Cloud",
+        RunEnd::Died(DeathCause::Fell) => "Fell out of reach",
+        RunEnd::Quit => "Run abandoned",
+        RunEnd::TimeUp => "Time's up",
+    }
+}
+
+fn high_score_table(children: &mut ChildBuilder, title: &str, entries: &[HighScoreEntry]) {
+    if entries.is_empty() {
+        return;
+    }
+    children.header(title);
+    for (rank, entry) in entries.iter().enumerate() {
+        children.label(format!(
+            "{}. {}  (height {:.0}, {})",
+            rank + 1,
+            entry.score,
+            entry.height.max(0.0),
+            entry.date_string()
+        ));
+    }
+}
@@ -0,0 +1,21 @@
+//! Everything drawn with Bevy UI. Only compiled with the `ui` feature,
+//! the game itself never depends on anything in here.
+
+mod controls;
+mod hud;
+mod menus;
+mod settings;
+mod vignette;
+mod widgets;
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        controls::plugin,
+        hud::plugin,
+        menus::plugin,
+        settings::plugin,
+        vignette::plugin,
+    ));
+}
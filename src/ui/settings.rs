@@ -0,0 +1,66 @@
+//! The settings screen, with a row for each volume and one for reducing motion.
+
+use bevy::prelude::*;
+
+use super::widgets::{Containers, Widgets};
+use crate::{
+    screen::Screen,
+    settings::{Accessibility, AudioBus, AudioSettings, SettingsScreen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Settings), spawn_settings_screen)
+        .add_systems(
+            Update,
+            update_settings_screen.run_if(
+                in_state(Screen::Settings).and_then(
+                    resource_changed::<SettingsScreen>
+                        .or_else(resource_changed::<AudioSettings>)
+                        .or_else(resource_changed::<Accessibility>),
+                ),
+            ),
+        );
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+struct SettingsRow(usize);
+
+fn spawn_settings_screen(mut commands: Commands) {
+    commands
+        .ui_root()
+        .insert((Name::new("Settings screen"), StateScoped(Screen::Settings)))
+        .with_children(|children| {
+            children.header("Settings");
+            for index in 0..SettingsScreen::ROWS {
+                children.label("").insert(SettingsRow(index));
+            }
+            children.label("Up/Down to choose, Left/Right to change");
+            children.label("Escape or (B) to go back");
+        });
+}
+
+fn update_settings_screen(
+    settings_screen: Res<SettingsScreen>,
+    audio_settings: Res<AudioSettings>,
+    accessibility: Res<Accessibility>,
+    mut text_query: Query<(&mut Text, &SettingsRow)>,
+) {
+    for (mut text, row) in text_query.iter_mut() {
+        let marker = if row.0 == settings_screen.selected {
+            "> "
+        } else {
+            ""
+        };
+        text.sections[0].value = match AudioBus::ALL.get(row.0) {
+            Some(&bus) => format!("{marker}{bus}: {:.0}%", audio_settings.volume(bus) * 100.0),
+            None => {
+                let state = if accessibility.reduce_motion {
+                    "On"
+                } else {
+                    "Off"
+                };
+                format!("{marker}Reduce motion: {state}")
+            }
+        };
+    }
+}
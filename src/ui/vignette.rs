@@ -0,0 +1,71 @@
+//! A red screen-edge vignette that grows stronger the closer the player is to a hazard.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{DamageSource, Player},
+    screen::Screen,
+    settings::Accessibility,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::InGame), spawn_vignette)
+        .add_systems(Update, update_vignette.run_if(in_state(Screen::InGame)));
+}
+
+/// The vignette is faked with stacked translucent borders of increasing width,
+/// so the screen edge is covered by every layer and the inside by fewer.
+const LAYERS: usize = 4;
+const LAYER_WIDTH: f32 = 12.0;
+const MAX_ALPHA: f32 = 0.6;
+/// Hazards further away than this don't show up at all.
+const OUTER_DISTANCE: f32 = 220.0;
+/// Hazards closer than this show the vignette at full strength.
+const INNER_DISTANCE: f32 = 40.0;
+
+#[derive(Component)]
+struct VignetteLayer;
+
+fn spawn_vignette(mut commands: Commands) {
+    for layer in 0..LAYERS {
+        commands.spawn((
+            Name::new("Danger vignette"),
+            VignetteLayer,
+            StateScoped(Screen::InGame),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    border: UiRect::all(Val::Px(LAYER_WIDTH * (layer + 1) as f32)),
+                    ..default()
+                },
+                border_color: Color::NONE.into(),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn update_vignette(
+    accessibility: Res<Accessibility>,
+    player_query: Query<&Transform, With<Player>>,
+    damage_source_query: Query<&Transform, With<DamageSource>>,
+    mut layer_query: Query<&mut BorderColor, With<VignetteLayer>>,
+) {
+    let intensity = match player_query.get_single() {
+        Ok(player_transform) if !accessibility.reduce_motion => {
+            let player_pos = player_transform.translation.truncate();
+            let nearest = damage_source_query
+                .iter()
+                .map(|transform| player_pos.distance(transform.translation.truncate()))
+                .fold(f32::INFINITY, f32::min);
+            ((OUTER_DISTANCE - nearest) / (OUTER_DISTANCE - INNER_DISTANCE)).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    };
+    let color = Color::srgba(0.8, 0.0, 0.0, intensity * MAX_ALPHA / LAYERS as f32);
+    for mut border_color in layer_query.iter_mut() {
+        border_color.0 = color;
+    }
+}
@@ -0,0 +1,65 @@
+//! Helper traits for spawning the few UI elements the game uses.
+
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+pub const HEADER_SIZE: f32 = 48.0;
+pub const LABEL_SIZE: f32 = 24.0;
+
+pub trait Containers {
+    /// A full-screen node that centers its children in a column.
+    fn ui_root(&mut self) -> EntityCommands<'_>;
+}
+
+impl Containers for Commands<'_, '_> {
+    fn ui_root(&mut self) -> EntityCommands<'_> {
+        self.spawn((
+            Name::new("UI Root"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+    }
+}
+
+pub trait Widgets {
+    fn header(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
+    fn label(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
+}
+
+impl Widgets for ChildBuilder<'_> {
+    fn header(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
+        self.spawn((
+            Name::new("Header"),
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: HEADER_SIZE,
+                    ..default()
+                },
+            ),
+        ))
+    }
+
+    fn label(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
+        self.spawn((
+            Name::new("Label"),
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: LABEL_SIZE,
+                    ..default()
+                },
+            ),
+        ))
+    }
+}